@@ -2,6 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use claude_transcript::TranscriptEntry;
 use claude_transcript::parse::parse_transcript_with_context;
+use std::cell::Cell;
 use std::fs;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Style, ThemeSet};
@@ -39,6 +40,7 @@ pub struct JsonHighlighter {
     ps: SyntaxSet,
     ts: ThemeSet,
     enabled: bool,
+    warned: Cell<bool>,
 }
 
 impl JsonHighlighter {
@@ -47,13 +49,23 @@ impl JsonHighlighter {
             ps: SyntaxSet::load_defaults_newlines(),
             ts: ThemeSet::load_defaults(),
             enabled: color_mode.should_colorize(),
+            warned: Cell::new(false),
         }
     }
 
     pub fn print_json(&self, json: &str) -> Result<()> {
         if self.enabled {
-            let syntax = self.ps.find_syntax_by_extension("json").unwrap();
-            let mut h = HighlightLines::new(syntax, &self.ts.themes["base16-ocean.dark"]);
+            let (Some(syntax), Some(theme)) = (
+                self.ps.find_syntax_by_extension("json"),
+                self.ts.themes.get("base16-ocean.dark"),
+            ) else {
+                if !self.warned.replace(true) {
+                    eprintln!("Warning: JSON syntax highlighting unavailable, printing plain JSON");
+                }
+                print!("{json}");
+                return Ok(());
+            };
+            let mut h = HighlightLines::new(syntax, theme);
 
             for line in json.lines() {
                 let ranges: Vec<(Style, &str)> = h.highlight_line(line, &self.ps)?;
@@ -135,7 +147,7 @@ fn display_single_transcript(path: String, color_mode: ColorMode, verify: bool)
         let parse_result = parse_transcript_with_context(&content);
 
         // If there are parsing errors, show those
-        if !parse_result.errors.is_empty() {
+        if !parse_result.is_ok() {
             for error in &parse_result.errors {
                 eprintln!("{}:{}: {}", path, error.line_number, error.json_error);
             }
@@ -148,7 +160,7 @@ fn display_single_transcript(path: String, color_mode: ColorMode, verify: bool)
         // Non-strict mode: parse and display what we can
         let parse_result = parse_transcript_with_context(&content);
 
-        if !parse_result.errors.is_empty() {
+        if !parse_result.is_ok() {
             eprintln!(
                 "\x1b[93mWarning: {} lines could not be parsed\x1b[0m",
                 parse_result.errors.len()
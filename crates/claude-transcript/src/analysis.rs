@@ -0,0 +1,527 @@
+use crate::{
+    ContentBlock, MessageContent, ToolResultContent, TranscriptEntry, TranscriptMessage, UsageInfo,
+};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Check whether a transcript ends cleanly.
+///
+/// Returns true when the final assistant entry carries a non-null `stop_reason`
+/// (e.g. "end_turn" or "stop_sequence"). A transcript with no assistant entries,
+/// or whose last assistant entry is still streaming, is considered incomplete.
+pub fn is_complete(entries: &[TranscriptEntry]) -> bool {
+    entries
+        .iter()
+        .rev()
+        .find_map(|entry| match entry {
+            TranscriptEntry::Assistant(assistant) => Some(assistant),
+            _ => None,
+        })
+        .is_some_and(|assistant| match &assistant.message {
+            TranscriptMessage::Assistant { stop_reason, .. } => stop_reason.is_some(),
+            TranscriptMessage::User { .. } => false,
+        })
+}
+
+/// Find the git branch from the most recent entry that records one.
+///
+/// Only newer transcripts carry `gitBranch`, so this returns `None` for older
+/// ones as well as for sessions outside a git repository.
+pub fn current_branch(entries: &[TranscriptEntry]) -> Option<&str> {
+    entries.iter().rev().find_map(|entry| match entry {
+        TranscriptEntry::User(user) => user.git_branch.as_deref(),
+        TranscriptEntry::Assistant(assistant) => assistant.git_branch.as_deref(),
+        TranscriptEntry::System(system) => system.git_branch.as_deref(),
+        TranscriptEntry::Summary(_) => None,
+    })
+}
+
+/// Find the text of the most recent prompt the user typed.
+///
+/// User entries carrying tool results are skipped, since Claude Code records
+/// those as user messages too.
+pub fn last_user_message(entries: &[TranscriptEntry]) -> Option<String> {
+    entries.iter().rev().find_map(|entry| match entry {
+        TranscriptEntry::User(user) => user
+            .message
+            .content()
+            .filter(|content| content.count_tool_results() == 0)
+            .and_then(MessageContent::as_text),
+        _ => None,
+    })
+}
+
+/// Find the text of Claude's most recent reply.
+///
+/// Assistant entries without any text, such as those that only use tools, are
+/// skipped.
+pub fn last_assistant_message(entries: &[TranscriptEntry]) -> Option<String> {
+    entries.iter().rev().find_map(|entry| match entry {
+        TranscriptEntry::Assistant(assistant) => assistant
+            .message
+            .content()
+            .and_then(MessageContent::as_text),
+        _ => None,
+    })
+}
+
+/// Collect the paths targeted by `rm` commands run through the Bash tool.
+///
+/// Parsing is best-effort: commands are split on `;`, `&&`, `||`, `|` and `&`,
+/// quotes and backslash escapes are honoured, a leading `sudo` is skipped, and
+/// every non-option argument to `rm` is reported (options are ignored until a
+/// `--`). Variables, globs and command substitutions are returned verbatim
+/// rather than expanded, and deletions through other means (`find -delete`,
+/// `xargs rm`, `unlink`, scripts) aren't detected.
+pub fn deleted_paths(entries: &[TranscriptEntry]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for tool_use in tool_uses(entries) {
+        if !tool_use.name.eq_ignore_ascii_case("bash") {
+            continue;
+        }
+        let Some(command) = tool_use.input.get("command").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        for words in split_commands(command) {
+            let mut words = words.iter().map(String::as_str).peekable();
+            if words.peek() == Some(&"sudo") {
+                words.next();
+            }
+            if !words
+                .next()
+                .is_some_and(|program| program == "rm" || program.ends_with("/rm"))
+            {
+                continue;
+            }
+            let mut options_done = false;
+            for word in words {
+                if !options_done && word == "--" {
+                    options_done = true;
+                } else if options_done || !word.starts_with('-') {
+                    paths.push(word.to_string());
+                }
+            }
+        }
+    }
+    paths
+}
+
+/// Find `TODO` and `FIXME` markers added by Edit, MultiEdit and Write tool uses.
+///
+/// Returns `(file_path, line)` pairs, with the line trimmed, for every line of
+/// added content (`new_string` or `content`) containing a marker. This is a
+/// plain substring match, so markers that were already present in replaced
+/// text are reported again.
+pub fn introduced_todos(entries: &[TranscriptEntry]) -> Vec<(String, String)> {
+    let mut todos = Vec::new();
+    for tool_use in tool_uses(entries) {
+        let Some(file_path) = tool_use.input.get("file_path").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let added: Vec<&Value> = match tool_use.name {
+            "Edit" => tool_use.input.get("new_string").into_iter().collect(),
+            "Write" => tool_use.input.get("content").into_iter().collect(),
+            "MultiEdit" => tool_use
+                .input
+                .get("edits")
+                .and_then(|edits| edits.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|edit| edit.get("new_string"))
+                .collect(),
+            _ => continue,
+        };
+
+        for line in added.iter().filter_map(|v| v.as_str()).flat_map(str::lines) {
+            if line.contains("TODO") || line.contains("FIXME") {
+                todos.push((file_path.to_string(), line.trim().to_string()));
+            }
+        }
+    }
+    todos
+}
+
+/// A borrowed view of a single tool use in a transcript.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToolUseRef<'a> {
+    /// Name of the tool, e.g. "Edit" or "Bash"
+    pub name: &'a str,
+    /// The tool's input parameters
+    pub input: &'a Value,
+    /// Uuid of the assistant entry the tool use belongs to
+    pub uuid: &'a str,
+    /// Tool use id, only present for `ContentBlock::ToolUse` blocks
+    pub id: Option<&'a str>,
+}
+
+/// Collect every tool use made by the assistant, in transcript order.
+///
+/// Includes both `ContentBlock::ToolUse` blocks in message content and the
+/// `tool_uses` field of assistant messages. Within an entry, content blocks
+/// come first.
+pub fn tool_uses(entries: &[TranscriptEntry]) -> Vec<ToolUseRef<'_>> {
+    let mut uses = Vec::new();
+    for entry in entries {
+        let TranscriptEntry::Assistant(assistant) = entry else {
+            continue;
+        };
+        let TranscriptMessage::Assistant {
+            content, tool_uses, ..
+        } = &assistant.message
+        else {
+            continue;
+        };
+        if let Some(MessageContent::Blocks(blocks)) = content {
+            for block in blocks {
+                if let ContentBlock::ToolUse { id, name, input } = block {
+                    uses.push(ToolUseRef {
+                        name,
+                        input,
+                        uuid: &assistant.uuid,
+                        id: Some(id),
+                    });
+                }
+            }
+        }
+        for tool_use in tool_uses.iter().flatten() {
+            uses.push(ToolUseRef {
+                name: &tool_use.tool_name,
+                input: &tool_use.tool_input,
+                uuid: &assistant.uuid,
+                id: None,
+            });
+        }
+    }
+    uses
+}
+
+/// A tool use paired with its result, if the result has been recorded yet.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolInteraction<'a> {
+    /// Tool use id linking the use to its result
+    pub id: &'a str,
+    /// Name of the tool, e.g. "Edit" or "Bash"
+    pub name: &'a str,
+    /// The tool's input parameters
+    pub input: &'a Value,
+    /// The tool's result, or `None` if it hasn't been recorded yet
+    pub result: Option<&'a ToolResultContent>,
+    /// Whether the result was marked as an error
+    pub is_error: bool,
+    /// Timestamp of the assistant entry that used the tool
+    pub started_at: &'a str,
+    /// Timestamp of the user entry carrying the result
+    pub finished_at: Option<&'a str>,
+}
+
+/// Pair every tool use with its result, in the order the tools were used.
+///
+/// Uses and results are correlated by tool use id across entries, so only
+/// `ContentBlock::ToolUse` blocks are included: the assistant `tool_uses`
+/// field has no ids. A use whose result hasn't been written yet, e.g. in a
+/// transcript read while the tool is running, has no result or finish time.
+pub fn tool_interactions(entries: &[TranscriptEntry]) -> Vec<ToolInteraction<'_>> {
+    let results = tool_results(entries);
+
+    let mut interactions = Vec::new();
+    for entry in entries {
+        let TranscriptEntry::Assistant(assistant) = entry else {
+            continue;
+        };
+        let Some(MessageContent::Blocks(blocks)) = assistant.message.content() else {
+            continue;
+        };
+        for block in blocks {
+            if let ContentBlock::ToolUse { id, name, input } = block {
+                let result = results.get(id.as_str());
+                interactions.push(ToolInteraction {
+                    id,
+                    name,
+                    input,
+                    result: result.map(|result| result.content),
+                    is_error: result.is_some_and(|result| result.is_error),
+                    started_at: &assistant.timestamp,
+                    finished_at: result.map(|result| result.timestamp),
+                });
+            }
+        }
+    }
+    interactions
+}
+
+/// A borrowed view of a single tool result in a transcript.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolResultRef<'a> {
+    /// Id of the tool use this is the result of
+    pub tool_use_id: &'a str,
+    /// The result returned to Claude
+    pub content: &'a ToolResultContent,
+    /// Whether the result was marked as an error
+    pub is_error: bool,
+    /// Uuid of the user entry carrying the result
+    pub uuid: &'a str,
+    /// Timestamp of the user entry carrying the result
+    pub timestamp: &'a str,
+}
+
+/// Pair every tool use from [`tool_uses`] with its result, in transcript order.
+///
+/// Results are matched to `ContentBlock::ToolUse` ids across entries. The
+/// result is `None` for uses that haven't finished yet and for uses from the
+/// assistant `tool_uses` field, which have no ids to match on.
+pub fn tool_use_pairs(
+    entries: &[TranscriptEntry],
+) -> Vec<(ToolUseRef<'_>, Option<ToolResultRef<'_>>)> {
+    let results = tool_results(entries);
+    tool_uses(entries)
+        .into_iter()
+        .map(|tool_use| {
+            let result = tool_use.id.and_then(|id| results.get(id).copied());
+            (tool_use, result)
+        })
+        .collect()
+}
+
+/// Collect every tool result marked as an error, in transcript order.
+///
+/// Claude Code marks results as errors both when a tool fails and when a hook
+/// blocks it, so this includes feedback injected by blocking hooks.
+pub fn errored_tool_results(entries: &[TranscriptEntry]) -> Vec<&ContentBlock> {
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            TranscriptEntry::User(user) => match user.message.content() {
+                Some(MessageContent::Blocks(blocks)) => Some(blocks),
+                _ => None,
+            },
+            _ => None,
+        })
+        .flatten()
+        .filter(|block| block.is_error())
+        .collect()
+}
+
+/// Collect the model's reasoning from assistant messages, in transcript order.
+///
+/// For each message, the top-level `thinking` field comes before any thinking
+/// blocks in its content.
+pub fn thinking_blocks(entries: &[TranscriptEntry]) -> Vec<&str> {
+    let mut thinking = Vec::new();
+    for entry in entries {
+        let TranscriptEntry::Assistant(assistant) = entry else {
+            continue;
+        };
+        let TranscriptMessage::Assistant {
+            content,
+            thinking: message_thinking,
+            ..
+        } = &assistant.message
+        else {
+            continue;
+        };
+        thinking.extend(message_thinking.as_deref());
+        if let Some(MessageContent::Blocks(blocks)) = content {
+            thinking.extend(blocks.iter().filter_map(|block| match block {
+                ContentBlock::Thinking { thinking, .. } => Some(thinking.as_str()),
+                _ => None,
+            }));
+        }
+    }
+    thinking
+}
+
+/// Keep only the entries from subagents (`sidechain` true) or only those from
+/// the main conversation (`sidechain` false).
+pub fn filter_sidechain(entries: Vec<TranscriptEntry>, sidechain: bool) -> Vec<TranscriptEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.is_sidechain() == sidechain)
+        .collect()
+}
+
+/// Reconstruct the conversation thread ending at `leaf_uuid` by following
+/// `parent_uuid` links back to the root, returning the entries root-first.
+///
+/// The walk stops at an entry without a parent, at a parent that isn't in
+/// `entries`, or when a link would revisit an entry, so a malformed transcript
+/// with a cycle still terminates. Returns an empty vector if `leaf_uuid` isn't
+/// found.
+pub fn thread_to_root<'a>(
+    entries: &'a [TranscriptEntry],
+    leaf_uuid: &str,
+) -> Vec<&'a TranscriptEntry> {
+    let by_uuid: HashMap<&str, &TranscriptEntry> = entries
+        .iter()
+        .filter_map(|entry| Some((entry.uuid()?, entry)))
+        .collect();
+
+    let mut thread = Vec::new();
+    let mut seen = HashSet::new();
+    let mut next = Some(leaf_uuid);
+    while let Some(uuid) = next
+        && seen.insert(uuid)
+        && let Some(&entry) = by_uuid.get(uuid)
+    {
+        thread.push(entry);
+        next = entry.parent_uuid();
+    }
+    thread.reverse();
+    thread
+}
+
+/// Every tool result in the transcript, keyed by tool use id
+fn tool_results(entries: &[TranscriptEntry]) -> HashMap<&str, ToolResultRef<'_>> {
+    let mut results = HashMap::new();
+    for entry in entries {
+        let TranscriptEntry::User(user) = entry else {
+            continue;
+        };
+        let Some(MessageContent::Blocks(blocks)) = user.message.content() else {
+            continue;
+        };
+        for block in blocks {
+            if let ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } = block
+            {
+                results.insert(
+                    tool_use_id.as_str(),
+                    ToolResultRef {
+                        tool_use_id,
+                        content,
+                        is_error: is_error.unwrap_or(false),
+                        uuid: &user.uuid,
+                        timestamp: &user.timestamp,
+                    },
+                );
+            }
+        }
+    }
+    results
+}
+
+/// Find every use of the tool called `name`, ignoring case.
+///
+/// Covers both `ContentBlock::ToolUse` blocks and the assistant `tool_uses`
+/// field. Content blocks have no [`ToolUse`](crate::ToolUse) struct to borrow,
+/// so results are returned as [`ToolUseRef`] views, whose `input` holds the
+/// tool arguments.
+pub fn find_tool_uses_by_name<'a>(
+    entries: &'a [TranscriptEntry],
+    name: &str,
+) -> Vec<ToolUseRef<'a>> {
+    tool_uses(entries)
+        .into_iter()
+        .filter(|tool_use| tool_use.name.eq_ignore_ascii_case(name))
+        .collect()
+}
+
+/// Count how many times each tool was used, keyed by tool name.
+///
+/// Counts are taken from [`tool_uses`], so both content blocks and the
+/// assistant `tool_uses` field are included.
+pub fn tool_frequency(entries: &[TranscriptEntry]) -> BTreeMap<String, usize> {
+    let mut frequency = BTreeMap::new();
+    for tool_use in tool_uses(entries) {
+        *frequency.entry(tool_use.name.to_string()).or_insert(0) += 1;
+    }
+    frequency
+}
+
+/// Sum token usage across every assistant entry.
+///
+/// Counts missing from an entry are treated as zero, so every count in the
+/// result is `Some`, even for a transcript with no assistant entries.
+pub fn total_usage(entries: &[TranscriptEntry]) -> UsageInfo {
+    let mut total = UsageInfo::zero();
+    for (_, usage) in assistant_usage(entries) {
+        total.add(usage);
+    }
+    total
+}
+
+/// Sum token usage per assistant `model`, as [`total_usage`] does overall
+pub fn per_model_usage(entries: &[TranscriptEntry]) -> HashMap<String, UsageInfo> {
+    let mut per_model: HashMap<String, UsageInfo> = HashMap::new();
+    for (model, usage) in assistant_usage(entries) {
+        per_model
+            .entry(model.to_string())
+            .or_insert_with(UsageInfo::zero)
+            .add(usage);
+    }
+    per_model
+}
+
+/// The model and usage of each assistant entry
+fn assistant_usage(entries: &[TranscriptEntry]) -> impl Iterator<Item = (&str, &UsageInfo)> {
+    entries.iter().filter_map(|entry| match entry {
+        TranscriptEntry::Assistant(assistant) => match &assistant.message {
+            TranscriptMessage::Assistant { model, usage, .. } => Some((model.as_str(), usage)),
+            TranscriptMessage::User { .. } => None,
+        },
+        _ => None,
+    })
+}
+
+/// Split a shell command line into commands, each a list of words.
+///
+/// Handles single and double quotes, backslash escapes, and the `;`, `&`, `|`
+/// and newline separators (which also covers `&&` and `||`).
+fn split_commands(command: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            ';' | '&' | '|' | '\n' => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
+}
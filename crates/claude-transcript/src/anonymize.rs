@@ -0,0 +1,125 @@
+use crate::TranscriptEntry;
+use serde_json::Value;
+
+/// Options controlling what [`anonymize`] scrubs.
+#[derive(Debug, Clone)]
+pub struct AnonymizeOptions {
+    /// Home directory to replace with `~` wherever it appears. Defaults to `$HOME`.
+    pub home_dir: Option<String>,
+    /// Replace tool results and outputs with a placeholder
+    pub strip_tool_outputs: bool,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        Self {
+            home_dir: std::env::var("HOME").ok().filter(|home| !home.is_empty()),
+            strip_tool_outputs: false,
+        }
+    }
+}
+
+/// Placeholder for stripped tool output
+pub const STRIPPED: &str = "[stripped]";
+
+/// Produce a de-identified copy of a transcript, suitable for sharing.
+///
+/// - The home directory is replaced with `~` in every string, including paths
+///   embedded in message text.
+/// - Session ids are replaced with a stable hash, so entries from the same
+///   session still line up.
+/// - With `strip_tool_outputs`, tool results and outputs are replaced with
+///   [`STRIPPED`].
+///
+/// Entry structure and every other field are preserved. Fails, rather than
+/// returning an entry unscrubbed, if an entry can't be converted to JSON or
+/// is no longer a valid entry once scrubbed.
+pub fn anonymize(
+    entries: Vec<TranscriptEntry>,
+    opts: AnonymizeOptions,
+) -> Result<Vec<TranscriptEntry>, serde_json::Error> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let mut value = serde_json::to_value(&entry)?;
+            scrub(&mut value, &opts);
+            serde_json::from_value(value)
+        })
+        .collect()
+}
+
+fn scrub(value: &mut Value, opts: &AnonymizeOptions) {
+    match value {
+        Value::String(s) => {
+            if let Some(home) = &opts.home_dir {
+                *s = replace_home(s, home);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub(item, opts)),
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match key.as_str() {
+                    "sessionId" => {
+                        if let Value::String(id) = field {
+                            *id = hash_session_id(id);
+                        }
+                    }
+                    "toolUseResult" | "toolOutput" if opts.strip_tool_outputs => {
+                        *field = Value::String(STRIPPED.to_string());
+                    }
+                    _ => scrub(field, opts),
+                }
+            }
+            if opts.strip_tool_outputs
+                && map.get("type").and_then(|t| t.as_str()) == Some("tool_result")
+            {
+                map.insert("content".to_string(), Value::String(STRIPPED.to_string()));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace `home` with `~` where it appears as a whole path component prefix,
+/// that is wherever it isn't followed by more of a file name
+fn replace_home(s: &str, home: &str) -> String {
+    let home = home.trim_end_matches(['/', '\\']);
+    if home.is_empty() || !s.contains(home) {
+        return s.to_string();
+    }
+
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find(home) {
+        let after = &rest[pos + home.len()..];
+        result.push_str(&rest[..pos]);
+        if continues_name(after) {
+            result.push_str(home);
+        } else {
+            result.push('~');
+        }
+        rest = after;
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Whether `after` starts with more of a file name: a name character, or a `.`
+/// followed by one, as in `/home/alice2` or `/home/alice.old`
+fn continues_name(after: &str) -> bool {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut chars = after.chars();
+    match chars.next() {
+        Some('.') => chars.next().is_some_and(is_name_char),
+        Some(c) => is_name_char(c),
+        None => false,
+    }
+}
+
+/// Stable, non-reversible replacement for a session id (64-bit FNV-1a)
+fn hash_session_id(id: &str) -> String {
+    let hash = id.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("session-{hash:016x}")
+}
@@ -1,7 +1,11 @@
+pub mod analysis;
+pub mod anonymize;
 pub mod parse;
+pub mod schema;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::Path;
 
 /// Main enum that represents different types of transcript entries
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +17,77 @@ pub enum TranscriptEntry {
     System(SystemEntry),
 }
 
+impl TranscriptEntry {
+    /// The working directory the entry was recorded in. Summary entries don't
+    /// record one.
+    pub fn cwd(&self) -> Option<&Path> {
+        match self {
+            TranscriptEntry::User(user) => Some(Path::new(&user.cwd)),
+            TranscriptEntry::Assistant(assistant) => Some(Path::new(&assistant.cwd)),
+            TranscriptEntry::System(system) => Some(Path::new(&system.cwd)),
+            TranscriptEntry::Summary(_) => None,
+        }
+    }
+
+    /// The entry's text: the [`MessageContent::content_text`] of a message,
+    /// a system entry's content or a summary. `None` for a message without
+    /// content.
+    pub fn text(&self) -> Option<String> {
+        match self {
+            TranscriptEntry::User(user) => user.message.content().map(MessageContent::content_text),
+            TranscriptEntry::Assistant(assistant) => assistant
+                .message
+                .content()
+                .map(MessageContent::content_text),
+            TranscriptEntry::System(system) => Some(system.content.clone()),
+            TranscriptEntry::Summary(summary) => Some(summary.summary.clone()),
+        }
+    }
+
+    /// Whether the entry is part of a subagent's work rather than the main
+    /// conversation. Summary entries never are.
+    pub fn is_sidechain(&self) -> bool {
+        match self {
+            TranscriptEntry::User(user) => user.is_sidechain,
+            TranscriptEntry::Assistant(assistant) => assistant.is_sidechain,
+            TranscriptEntry::System(system) => system.is_sidechain,
+            TranscriptEntry::Summary(_) => false,
+        }
+    }
+
+    /// When the entry was recorded, as an RFC 3339 string. Summary entries
+    /// don't record one.
+    pub fn timestamp(&self) -> Option<&str> {
+        match self {
+            TranscriptEntry::User(user) => Some(&user.timestamp),
+            TranscriptEntry::Assistant(assistant) => Some(&assistant.timestamp),
+            TranscriptEntry::System(system) => Some(&system.timestamp),
+            TranscriptEntry::Summary(_) => None,
+        }
+    }
+
+    /// The entry's id. Summary entries don't have one.
+    pub fn uuid(&self) -> Option<&str> {
+        match self {
+            TranscriptEntry::User(user) => Some(&user.uuid),
+            TranscriptEntry::Assistant(assistant) => Some(&assistant.uuid),
+            TranscriptEntry::System(system) => Some(&system.uuid),
+            TranscriptEntry::Summary(_) => None,
+        }
+    }
+
+    /// The id of the entry this one follows. `None` for the first message of a
+    /// conversation and for summary entries.
+    pub fn parent_uuid(&self) -> Option<&str> {
+        match self {
+            TranscriptEntry::User(user) => user.parent_uuid.as_deref(),
+            TranscriptEntry::Assistant(assistant) => Some(&assistant.parent_uuid),
+            TranscriptEntry::System(system) => Some(&system.parent_uuid),
+            TranscriptEntry::Summary(_) => None,
+        }
+    }
+}
+
 /// User message entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +103,8 @@ pub struct UserEntry {
     pub parent_uuid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_use_result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
 }
 
 /// Assistant message entry
@@ -47,6 +124,8 @@ pub struct AssistantEntry {
     pub request_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_api_error_message: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
 }
 
 /// Summary entry
@@ -75,6 +154,8 @@ pub struct SystemEntry {
     pub level: Option<String>,
     #[serde(rename = "toolUseID", skip_serializing_if = "Option::is_none")]
     pub tool_use_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
 }
 
 /// Message can be either from a user or an assistant
@@ -155,6 +236,41 @@ impl MessageContent {
                 .count(),
         }
     }
+
+    /// Get the text of the content, joining text blocks with newlines. Returns
+    /// `None` when there are no text blocks.
+    pub fn as_text(&self) -> Option<String> {
+        match self {
+            MessageContent::Text(text) => Some(text.clone()),
+            MessageContent::Blocks(blocks) => {
+                let texts: Vec<&str> = blocks
+                    .iter()
+                    .filter_map(|b| match b {
+                        ContentBlock::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                (!texts.is_empty()).then(|| texts.join("\n"))
+            }
+        }
+    }
+
+    /// Get all the text of the content, joining text blocks and tool result
+    /// text with newlines. Thinking and tool use blocks are skipped.
+    pub fn content_text(&self) -> String {
+        match self {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Blocks(blocks) => blocks
+                .iter()
+                .filter_map(|b| match b {
+                    ContentBlock::Text { text } => Some(text.clone()),
+                    ContentBlock::ToolResult { content, .. } => Some(content.joined_text()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
 }
 
 /// Tool result content can be either a string or an array of content items
@@ -165,6 +281,20 @@ pub enum ToolResultContent {
     Array(Vec<ToolResultItem>),
 }
 
+impl ToolResultContent {
+    /// Get the text of the result, joining array items with newlines
+    pub fn joined_text(&self) -> String {
+        match self {
+            ToolResultContent::Text(text) => text.clone(),
+            ToolResultContent::Array(items) => items
+                .iter()
+                .map(|item| item.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResultItem {
     #[serde(rename = "type")]
@@ -196,6 +326,20 @@ pub enum ContentBlock {
     },
 }
 
+impl ContentBlock {
+    /// Check whether this is a tool result marked as an error, e.g. feedback
+    /// from a hook that blocked the tool
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            ContentBlock::ToolResult {
+                is_error: Some(true),
+                ..
+            }
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ToolUse {
@@ -215,7 +359,7 @@ pub struct CodeOutput {
     pub language: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UsageInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_creation_input_tokens: Option<u64>,
@@ -228,3 +372,33 @@ pub struct UsageInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service_tier: Option<String>,
 }
+
+impl UsageInfo {
+    /// Usage with every token count set to zero
+    pub fn zero() -> Self {
+        Self {
+            cache_creation_input_tokens: Some(0),
+            cache_read_input_tokens: Some(0),
+            input_tokens: Some(0),
+            output_tokens: Some(0),
+            service_tier: None,
+        }
+    }
+
+    /// Add another message's token counts to this one, treating missing counts as zero
+    pub fn add(&mut self, other: &UsageInfo) {
+        fn sum(total: &mut Option<u64>, value: Option<u64>) {
+            *total = Some(total.unwrap_or(0) + value.unwrap_or(0));
+        }
+        sum(
+            &mut self.cache_creation_input_tokens,
+            other.cache_creation_input_tokens,
+        );
+        sum(
+            &mut self.cache_read_input_tokens,
+            other.cache_read_input_tokens,
+        );
+        sum(&mut self.input_tokens, other.input_tokens);
+        sum(&mut self.output_tokens, other.output_tokens);
+    }
+}
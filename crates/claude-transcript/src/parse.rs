@@ -1,6 +1,11 @@
 use crate::TranscriptEntry;
 use serde_json;
 use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 
 /// Error type for transcript parsing with detailed context
 #[derive(Debug)]
@@ -38,6 +43,18 @@ pub struct TranscriptParseResult {
     pub errors: Vec<TranscriptParseError>,
 }
 
+impl TranscriptParseResult {
+    /// Check whether every line parsed
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The error for the first line that failed to parse, if any
+    pub fn first_error(&self) -> Option<&TranscriptParseError> {
+        self.errors.first()
+    }
+}
+
 pub fn parse_transcript_line(line: &str) -> Result<TranscriptEntry, serde_json::Error> {
     serde_json::from_str(line)
 }
@@ -74,3 +91,156 @@ pub fn parse_transcript_with_context(content: &str) -> TranscriptParseResult {
 
     TranscriptParseResult { entries, errors }
 }
+
+/// Lazily parse transcript entries from a reader, one line at a time.
+///
+/// Unlike [`parse_transcript`], this never holds the whole transcript in memory.
+/// Empty lines are skipped, and errors carry 1-indexed line numbers exactly as
+/// [`parse_transcript_with_context`] reports them. Iteration stops after an I/O error.
+pub fn parse_transcript_iter<R: BufRead>(reader: R) -> TranscriptIter<R> {
+    TranscriptIter {
+        reader,
+        line_number: 0,
+        position: 0,
+        line_offset: 0,
+        buffer: Vec::new(),
+        done: false,
+    }
+}
+
+/// Stream a transcript from a reader and collect every line that fails to parse.
+///
+/// Successfully parsed entries are dropped as soon as they're read, so memory
+/// use doesn't grow with the size of the transcript. An I/O error is reported
+/// as the final error.
+pub fn validate_transcript_streaming<R: BufRead>(reader: R) -> Vec<TranscriptParseError> {
+    parse_transcript_iter(reader)
+        .filter_map(Result::err)
+        .collect()
+}
+
+/// Parse several transcript files, such as those of a resumed session, and
+/// merge their entries in chronological order.
+///
+/// Entries are sorted by their RFC 3339 `timestamp`. Summary entries, which
+/// have no timestamp, and entries whose timestamp can't be parsed go at the
+/// end. Entries with equal timestamps keep their order from `paths`. Fails on
+/// the first file that can't be read or line that can't be parsed, naming the
+/// file in the error.
+pub fn parse_and_merge(paths: &[&Path]) -> io::Result<Vec<TranscriptEntry>> {
+    let mut entries = Vec::new();
+    for path in paths {
+        let file = File::open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+        for entry in parse_transcript_iter(BufReader::new(file)) {
+            let entry = entry.map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e}", path.display()),
+                )
+            })?;
+            entries.push(entry);
+        }
+    }
+
+    entries.sort_by_cached_key(|entry| {
+        let time = entry
+            .timestamp()
+            .and_then(|timestamp| OffsetDateTime::parse(timestamp, &Rfc3339).ok());
+        (time.is_none(), time)
+    });
+    Ok(entries)
+}
+
+/// Iterator over transcript entries, created by [`parse_transcript_iter`]
+pub struct TranscriptIter<R> {
+    reader: R,
+    line_number: usize,
+    /// Bytes read so far
+    position: u64,
+    /// Byte offset of the start of the most recently read line
+    line_offset: u64,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> TranscriptIter<R> {
+    /// Yield each entry with the byte offset of the start of its line.
+    ///
+    /// Offsets are relative to where the reader started, so seeking a file to
+    /// an entry's offset and parsing one line re-reads that entry.
+    pub fn with_offsets(self) -> TranscriptOffsetIter<R> {
+        TranscriptOffsetIter { inner: self }
+    }
+}
+
+impl<R: BufRead> Iterator for TranscriptIter<R> {
+    type Item = Result<TranscriptEntry, TranscriptParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            // Read bytes rather than a String, so a line that isn't valid
+            // UTF-8 is reported like any other bad line instead of ending
+            // the iteration
+            let read = self.reader.read_until(b'\n', &mut self.buffer);
+            match read {
+                Ok(0) => self.done = true,
+                Ok(read) => {
+                    self.line_number += 1;
+                    self.line_offset = self.position;
+                    self.position += read as u64;
+                    let bytes = self.buffer.strip_suffix(b"\n").unwrap_or(&self.buffer);
+                    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+                    let line = match std::str::from_utf8(bytes) {
+                        Ok(line) => line,
+                        Err(e) => {
+                            return Some(Err(TranscriptParseError {
+                                line_number: self.line_number,
+                                line_content: String::from_utf8_lossy(bytes).into_owned(),
+                                json_error: serde_json::Error::io(io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    e,
+                                )),
+                            }));
+                        }
+                    };
+                    if line.is_empty() {
+                        continue;
+                    }
+                    return Some(parse_transcript_line(line).map_err(|json_error| {
+                        TranscriptParseError {
+                            line_number: self.line_number,
+                            line_content: line.to_string(),
+                            json_error,
+                        }
+                    }));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(TranscriptParseError {
+                        line_number: self.line_number + 1,
+                        line_content: String::new(),
+                        json_error: serde_json::Error::io(e),
+                    }));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over transcript entries and their byte offsets, created by
+/// [`TranscriptIter::with_offsets`]
+pub struct TranscriptOffsetIter<R> {
+    inner: TranscriptIter<R>,
+}
+
+impl<R: BufRead> Iterator for TranscriptOffsetIter<R> {
+    type Item = Result<(u64, TranscriptEntry), TranscriptParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.inner.next()?;
+        Some(entry.map(|entry| (self.inner.line_offset, entry)))
+    }
+}
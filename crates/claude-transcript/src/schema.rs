@@ -0,0 +1,105 @@
+use semver::Version;
+use serde_json::Value;
+use std::fmt;
+
+/// A field an entry type must carry from a given Claude Code version onwards
+struct FieldRule {
+    entry_type: &'static str,
+    field: &'static str,
+    since: (u64, u64, u64),
+}
+
+/// Fields present on every user, assistant and system entry
+const MESSAGE_FIELDS: &[&str] = &[
+    "uuid",
+    "timestamp",
+    "cwd",
+    "sessionId",
+    "version",
+    "userType",
+    "isSidechain",
+];
+
+/// Fields added in later versions, with the earliest version they have been
+/// seen in transcripts
+const VERSIONED_RULES: &[FieldRule] = &[
+    FieldRule {
+        entry_type: "system",
+        field: "level",
+        since: (1, 0, 35),
+    },
+    FieldRule {
+        entry_type: "assistant",
+        field: "requestId",
+        since: (1, 0, 39),
+    },
+];
+
+/// A required field missing from a transcript entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaIssue {
+    /// Index of the entry in the validated slice
+    pub index: usize,
+    /// The entry's `type`
+    pub entry_type: String,
+    /// The missing field
+    pub field: String,
+    /// The version from which the field is required
+    pub since: Version,
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entry is missing `{}` (required since {})",
+            self.entry_type, self.field, self.since
+        )
+    }
+}
+
+/// Check raw transcript entries against the fields Claude Code `version` is
+/// expected to write.
+///
+/// This works on raw JSON so it can explain entries that fail to parse into
+/// [`TranscriptEntry`](crate::TranscriptEntry). Entries of unknown type are
+/// skipped, as is `requestId` on synthetic API error messages, which never
+/// carry one.
+pub fn validate_for_version(entries: &[Value], version: &Version) -> Vec<SchemaIssue> {
+    let mut issues = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(entry_type) = entry.get("type").and_then(|t| t.as_str()) else {
+            continue;
+        };
+
+        let base: &[&str] = match entry_type {
+            "user" | "assistant" | "system" => MESSAGE_FIELDS,
+            "summary" => &["summary", "leafUuid"],
+            _ => continue,
+        };
+        let required = base.iter().map(|&field| (field, (1, 0, 0))).chain(
+            VERSIONED_RULES
+                .iter()
+                .filter(|rule| rule.entry_type == entry_type)
+                .filter(|rule| !(rule.field == "requestId" && is_api_error(entry)))
+                .map(|rule| (rule.field, rule.since)),
+        );
+
+        for (field, (major, minor, patch)) in required {
+            let since = Version::new(major, minor, patch);
+            if *version >= since && entry.get(field).is_none() {
+                issues.push(SchemaIssue {
+                    index,
+                    entry_type: entry_type.to_string(),
+                    field: field.to_string(),
+                    since,
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn is_api_error(entry: &Value) -> bool {
+    entry.get("isApiErrorMessage").and_then(|v| v.as_bool()) == Some(true)
+}
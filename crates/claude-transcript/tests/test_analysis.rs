@@ -0,0 +1,436 @@
+use claude_transcript::analysis::{
+    ToolUseRef, current_branch, deleted_paths, errored_tool_results, filter_sidechain,
+    find_tool_uses_by_name, introduced_todos, is_complete, last_assistant_message,
+    last_user_message, per_model_usage, thinking_blocks, thread_to_root, tool_frequency,
+    tool_interactions, tool_use_pairs, tool_uses, total_usage,
+};
+use claude_transcript::parse::parse_transcript;
+use claude_transcript::{ContentBlock, TranscriptEntry};
+
+const USER_LINE: &str = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"u1","timestamp":"2025-01-01T00:00:00Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}"#;
+
+fn assistant_line(stop_reason: &str) -> String {
+    format!(
+        r#"{{"type":"assistant","message":{{"id":"msg_1","type":"message","role":"assistant","model":"test-model","content":[{{"type":"text","text":"hi"}}],"stop_reason":{stop_reason},"stop_sequence":null,"usage":{{"input_tokens":1,"output_tokens":1}}}},"uuid":"a1","timestamp":"2025-01-01T00:00:01Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}}"#
+    )
+}
+
+#[test]
+fn test_is_complete_clean_ending() {
+    let content = format!("{USER_LINE}\n{}", assistant_line(r#""end_turn""#));
+    let entries = parse_transcript(&content).unwrap();
+    assert!(is_complete(&entries));
+}
+
+#[test]
+fn test_is_complete_null_stop_reason() {
+    let content = format!("{USER_LINE}\n{}", assistant_line("null"));
+    let entries = parse_transcript(&content).unwrap();
+    assert!(!is_complete(&entries));
+
+    // No assistant entries at all
+    let entries = parse_transcript(USER_LINE).unwrap();
+    assert!(!is_complete(&entries));
+}
+
+fn bash_line(uuid: &str, command: &str) -> String {
+    tool_use_line(uuid, "Bash", serde_json::json!({ "command": command }))
+}
+
+fn tool_use_line(uuid: &str, name: &str, input: serde_json::Value) -> String {
+    format!(
+        r#"{{"type":"assistant","message":{{"id":"msg_{uuid}","type":"message","role":"assistant","model":"test-model","content":[{{"type":"tool_use","id":"toolu_{uuid}","name":"{name}","input":{input}}}],"stop_reason":null,"stop_sequence":null,"usage":{{"input_tokens":1,"output_tokens":1}}}},"uuid":"{uuid}","timestamp":"2025-01-01T00:00:01Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}}"#
+    )
+}
+
+#[test]
+fn test_deleted_paths_single_file() {
+    let entries = parse_transcript(&bash_line("a1", "rm file.txt")).unwrap();
+    assert_eq!(deleted_paths(&entries), vec!["file.txt"]);
+}
+
+#[test]
+fn test_deleted_paths_recursive_multiple_args() {
+    let content = [
+        bash_line("a1", "rm -rf dir sub"),
+        bash_line("a2", r#"cd /tmp && rm -f "my file.txt" && ls"#),
+        bash_line("a3", "echo rm nothing"),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+    assert_eq!(deleted_paths(&entries), vec!["dir", "sub", "my file.txt"]);
+}
+
+#[test]
+fn test_tool_uses_mixed_sources() {
+    let legacy = r#"{"type":"assistant","message":{"id":"msg_2","type":"message","role":"assistant","model":"test-model","content":"editing","tool_uses":[{"toolName":"Edit","toolInput":{"file_path":"src/lib.rs"}}],"stop_reason":null,"stop_sequence":null,"usage":{"input_tokens":1,"output_tokens":1}},"uuid":"a2","timestamp":"2025-01-01T00:00:02Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"a1","requestId":"req-2"}"#;
+    let content = [
+        USER_LINE.to_string(),
+        bash_line("a1", "ls"),
+        legacy.to_string(),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let uses = tool_uses(&entries);
+    assert_eq!(uses.len(), 2);
+    assert_eq!(
+        uses[0],
+        ToolUseRef {
+            name: "Bash",
+            input: &serde_json::json!({"command": "ls"}),
+            uuid: "a1",
+            id: Some("toolu_a1"),
+        }
+    );
+    assert_eq!(uses[1].name, "Edit");
+    assert_eq!(uses[1].input["file_path"], "src/lib.rs");
+    assert_eq!(uses[1].uuid, "a2");
+    assert_eq!(uses[1].id, None);
+}
+
+#[test]
+fn test_current_branch() {
+    let on_branch =
+        |branch: &str| USER_LINE.replace(r#""cwd""#, &format!(r#""gitBranch":"{branch}","cwd""#));
+
+    // Older entries without gitBranch
+    let entries = parse_transcript(USER_LINE).unwrap();
+    assert_eq!(current_branch(&entries), None);
+
+    let content = [
+        on_branch("feature"),
+        on_branch("main"),
+        assistant_line("null"),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+    let TranscriptEntry::User(user) = &entries[0] else {
+        panic!("expected user entry");
+    };
+    assert_eq!(user.git_branch.as_deref(), Some("feature"));
+    assert_eq!(current_branch(&entries), Some("main"));
+}
+
+#[test]
+fn test_introduced_todos() {
+    let content = [
+        tool_use_line(
+            "a1",
+            "Edit",
+            serde_json::json!({
+                "file_path": "src/lib.rs",
+                "old_string": "fn a() {}",
+                "new_string": "// TODO: handle errors\nfn a() {}"
+            }),
+        ),
+        tool_use_line(
+            "a2",
+            "Write",
+            serde_json::json!({ "file_path": "notes.md", "content": "nothing to see" }),
+        ),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+    assert_eq!(
+        introduced_todos(&entries),
+        vec![(
+            "src/lib.rs".to_string(),
+            "// TODO: handle errors".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_find_tool_uses_by_name() {
+    let content = [
+        bash_line("a1", "cargo build"),
+        tool_use_line(
+            "a2",
+            "Read",
+            serde_json::json!({ "file_path": "src/lib.rs" }),
+        ),
+        bash_line("a3", "cargo test"),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let bash = find_tool_uses_by_name(&entries, "bash");
+    let commands: Vec<_> = bash.iter().map(|u| u.input["command"].as_str()).collect();
+    assert_eq!(commands, vec![Some("cargo build"), Some("cargo test")]);
+
+    assert!(find_tool_uses_by_name(&entries, "WebFetch").is_empty());
+}
+
+fn usage_line(uuid: &str, model: &str, usage: serde_json::Value) -> String {
+    format!(
+        r#"{{"type":"assistant","message":{{"id":"msg_{uuid}","type":"message","role":"assistant","model":"{model}","content":[{{"type":"text","text":"hi"}}],"stop_reason":"end_turn","stop_sequence":null,"usage":{usage}}},"uuid":"{uuid}","timestamp":"2025-01-01T00:00:01Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}}"#
+    )
+}
+
+#[test]
+fn test_total_and_per_model_usage() {
+    let content = [
+        USER_LINE.to_string(),
+        usage_line(
+            "a1",
+            "model-a",
+            serde_json::json!({
+                "input_tokens": 10,
+                "output_tokens": 20,
+                "cache_creation_input_tokens": 100,
+                "cache_read_input_tokens": 1000
+            }),
+        ),
+        usage_line(
+            "a2",
+            "model-b",
+            serde_json::json!({ "input_tokens": 3, "output_tokens": 4 }),
+        ),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let total = total_usage(&entries);
+    assert_eq!(total.input_tokens, Some(13));
+    assert_eq!(total.output_tokens, Some(24));
+    assert_eq!(total.cache_creation_input_tokens, Some(100));
+    assert_eq!(total.cache_read_input_tokens, Some(1000));
+
+    let per_model = per_model_usage(&entries);
+    assert_eq!(per_model.len(), 2);
+    assert_eq!(per_model["model-a"].output_tokens, Some(20));
+    assert_eq!(per_model["model-b"].input_tokens, Some(3));
+    assert_eq!(per_model["model-b"].cache_read_input_tokens, Some(0));
+
+    assert_eq!(total_usage(&[]).input_tokens, Some(0));
+}
+
+#[test]
+fn test_tool_frequency() {
+    let content = [
+        bash_line("a1", "ls"),
+        tool_use_line("a2", "Read", serde_json::json!({ "file_path": "/a.rs" })),
+        bash_line("a3", "pwd"),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let frequency = tool_frequency(&entries);
+    assert_eq!(
+        frequency.into_iter().collect::<Vec<_>>(),
+        vec![("Bash".to_string(), 2), ("Read".to_string(), 1)]
+    );
+}
+
+fn tool_result_line(uuid: &str, tool_use_id: &str, text: &str, is_error: bool) -> String {
+    format!(
+        r#"{{"type":"user","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"{tool_use_id}","content":"{text}","is_error":{is_error}}}]}},"uuid":"{uuid}","timestamp":"2025-01-01T00:00:05Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"a1"}}"#
+    )
+}
+
+#[test]
+fn test_tool_interactions() {
+    let content = [
+        bash_line("a1", "cargo test"),
+        tool_result_line("u2", "toolu_a1", "test failed", true),
+        tool_use_line("a2", "Read", serde_json::json!({ "file_path": "/a.rs" })),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let interactions = tool_interactions(&entries);
+    assert_eq!(interactions.len(), 2);
+
+    let complete = &interactions[0];
+    assert_eq!(complete.id, "toolu_a1");
+    assert_eq!(complete.name, "Bash");
+    assert_eq!(complete.input["command"], "cargo test");
+    assert_eq!(
+        complete.result.map(|result| result.joined_text()),
+        Some("test failed".to_string())
+    );
+    assert!(complete.is_error);
+    assert_eq!(complete.started_at, "2025-01-01T00:00:01Z");
+    assert_eq!(complete.finished_at, Some("2025-01-01T00:00:05Z"));
+
+    let pending = &interactions[1];
+    assert_eq!(pending.name, "Read");
+    assert!(pending.result.is_none());
+    assert!(!pending.is_error);
+    assert_eq!(pending.finished_at, None);
+}
+
+#[test]
+fn test_last_assistant_message() {
+    let content = [
+        USER_LINE.to_string(),
+        assistant_line("\"tool_use\""),
+        bash_line("a2", "cargo test"),
+        tool_result_line("u2", "toolu_a2", "ok", false),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+    assert_eq!(last_assistant_message(&entries).as_deref(), Some("hi"));
+
+    let entries = parse_transcript(USER_LINE).unwrap();
+    assert_eq!(last_assistant_message(&entries), None);
+}
+
+#[test]
+fn test_last_user_message() {
+    let content = [
+        USER_LINE.to_string(),
+        bash_line("a1", "cargo test"),
+        tool_result_line("u2", "toolu_a1", "ok", false),
+        assistant_line("\"end_turn\""),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+    assert_eq!(last_user_message(&entries).as_deref(), Some("hello"));
+
+    let prompt = USER_LINE
+        .replace(
+            r#""content":"hello""#,
+            r#""content":[{"type":"text","text":"now add docs"}]"#,
+        )
+        .replace(r#""uuid":"u1""#, r#""uuid":"u3""#);
+    let content = format!("{content}\n{prompt}");
+    let entries = parse_transcript(&content).unwrap();
+    assert_eq!(last_user_message(&entries).as_deref(), Some("now add docs"));
+
+    let entries = parse_transcript(&assistant_line("null")).unwrap();
+    assert_eq!(last_user_message(&entries), None);
+}
+
+#[test]
+fn test_tool_use_pairs() {
+    let content = [
+        bash_line("a1", "cargo build"),
+        tool_result_line("u2", "toolu_a1", "compiled", false),
+        tool_use_line("a2", "Read", serde_json::json!({ "file_path": "/a.rs" })),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let pairs = tool_use_pairs(&entries);
+    assert_eq!(pairs.len(), 2);
+
+    let (tool_use, result) = &pairs[0];
+    assert_eq!(tool_use.name, "Bash");
+    let result = result.unwrap();
+    assert_eq!(result.tool_use_id, "toolu_a1");
+    assert_eq!(result.content.joined_text(), "compiled");
+    assert_eq!(result.uuid, "u2");
+    assert!(!result.is_error);
+
+    let (tool_use, result) = &pairs[1];
+    assert_eq!(tool_use.name, "Read");
+    assert!(result.is_none());
+}
+
+#[test]
+fn test_errored_tool_results() {
+    let content = [
+        bash_line("a1", "rm -rf /"),
+        tool_result_line("u2", "toolu_a1", "Blocked by hook", true),
+        bash_line("a2", "ls"),
+        tool_result_line("u3", "toolu_a2", "src", false),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let errors = errored_tool_results(&entries);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].is_error());
+    let ContentBlock::ToolResult {
+        tool_use_id,
+        content,
+        ..
+    } = errors[0]
+    else {
+        panic!("expected a tool result");
+    };
+    assert_eq!(tool_use_id, "toolu_a1");
+    assert_eq!(content.joined_text(), "Blocked by hook");
+}
+
+#[test]
+fn test_thinking_blocks() {
+    let thinking_line = r#"{"type":"assistant","message":{"id":"msg_2","type":"message","role":"assistant","model":"test-model","content":[{"type":"thinking","thinking":"The user wants a greeting.","signature":"sig"},{"type":"text","text":"hello"}],"stop_reason":"end_turn","stop_sequence":null,"usage":{"input_tokens":1,"output_tokens":1}},"uuid":"a2","timestamp":"2025-01-01T00:00:02Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"a1","requestId":"req-2"}"#;
+    let content = [
+        USER_LINE.to_string(),
+        assistant_line(r#""end_turn""#),
+        thinking_line.to_string(),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    assert_eq!(
+        thinking_blocks(&entries),
+        vec!["The user wants a greeting."]
+    );
+    assert!(thinking_blocks(&entries[..2]).is_empty());
+}
+
+#[test]
+fn test_filter_sidechain() {
+    let subagent_line = USER_LINE
+        .replace(r#""isSidechain":false"#, r#""isSidechain":true"#)
+        .replace(r#""uuid":"u1""#, r#""uuid":"u2""#);
+    let summary_line = r#"{"type":"summary","summary":"Greeting","leafUuid":"a1"}"#;
+    let content = [
+        USER_LINE.to_string(),
+        subagent_line,
+        assistant_line(r#""end_turn""#),
+        summary_line.to_string(),
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let sidechain = filter_sidechain(entries.clone(), true);
+    assert_eq!(sidechain.len(), 1);
+    assert!(matches!(&sidechain[0], TranscriptEntry::User(user) if user.uuid == "u2"));
+
+    let main = filter_sidechain(entries, false);
+    assert_eq!(main.len(), 3);
+    assert!(main.iter().all(|entry| !entry.is_sidechain()));
+}
+
+#[test]
+fn test_thread_to_root() {
+    let reply = USER_LINE
+        .replace(r#""uuid":"u1""#, r#""uuid":"u2""#)
+        .replace(r#""parentUuid":null"#, r#""parentUuid":"a1""#);
+    // An unrelated root that isn't part of the thread
+    let other = USER_LINE.replace(r#""uuid":"u1""#, r#""uuid":"u3""#);
+    let content = [
+        USER_LINE.to_string(),
+        other,
+        assistant_line(r#""end_turn""#),
+        reply,
+    ]
+    .join("\n");
+    let entries = parse_transcript(&content).unwrap();
+
+    let uuids: Vec<_> = thread_to_root(&entries, "u2")
+        .iter()
+        .filter_map(|entry| entry.uuid())
+        .collect();
+    assert_eq!(uuids, vec!["u1", "a1", "u2"]);
+
+    assert!(thread_to_root(&entries, "missing").is_empty());
+}
+
+#[test]
+fn test_thread_to_root_cycle() {
+    let looped = USER_LINE.replace(r#""parentUuid":null"#, r#""parentUuid":"a1""#);
+    let content = format!("{looped}\n{}", assistant_line(r#""end_turn""#));
+    let entries = parse_transcript(&content).unwrap();
+
+    let uuids: Vec<_> = thread_to_root(&entries, "a1")
+        .iter()
+        .filter_map(|entry| entry.uuid())
+        .collect();
+    assert_eq!(uuids, vec!["u1", "a1"]);
+}
@@ -0,0 +1,104 @@
+use claude_transcript::anonymize::{AnonymizeOptions, STRIPPED, anonymize};
+use claude_transcript::parse::parse_transcript;
+use claude_transcript::{ContentBlock, MessageContent, TranscriptEntry};
+
+const TRANSCRIPT: &str = r#"{"type":"user","message":{"role":"user","content":"look at /Users/alice/project/src/main.rs"},"uuid":"u1","timestamp":"2025-01-01T00:00:00Z","cwd":"/Users/alice/project","sessionId":"bea16101-83bb-4150-b58d-295de3267bf9","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"secret contents"}]},"uuid":"u2","timestamp":"2025-01-01T00:00:01Z","cwd":"/Users/alice2","sessionId":"bea16101-83bb-4150-b58d-295de3267bf9","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","toolUseResult":{"stdout":"secret contents"}}
+{"type":"summary","summary":"Edited main.rs","leafUuid":"u2"}"#;
+
+fn options(strip_tool_outputs: bool) -> AnonymizeOptions {
+    AnonymizeOptions {
+        home_dir: Some("/Users/alice".to_string()),
+        strip_tool_outputs,
+    }
+}
+
+#[test]
+fn test_anonymize_scrubs_home_and_session() {
+    let entries = parse_transcript(TRANSCRIPT).unwrap();
+    let anonymized = anonymize(entries, options(false)).unwrap();
+
+    // Structure is preserved
+    assert_eq!(anonymized.len(), 3);
+    let TranscriptEntry::User(first) = &anonymized[0] else {
+        panic!("expected user entry");
+    };
+    let TranscriptEntry::User(second) = &anonymized[1] else {
+        panic!("expected user entry");
+    };
+    assert!(matches!(anonymized[2], TranscriptEntry::Summary(_)));
+    assert_eq!(first.uuid, "u1");
+
+    assert_eq!(first.cwd, "~/project");
+    assert!(matches!(
+        first.message.content(),
+        Some(MessageContent::Text(text)) if text == "look at ~/project/src/main.rs"
+    ));
+    // Only whole path components are replaced
+    assert_eq!(second.cwd, "/Users/alice2");
+
+    // Session ids are hashed consistently
+    assert_ne!(first.session_id, "bea16101-83bb-4150-b58d-295de3267bf9");
+    assert!(first.session_id.starts_with("session-"));
+    assert_eq!(first.session_id, second.session_id);
+
+    // Tool outputs are kept unless stripping was requested
+    assert_eq!(
+        second.tool_use_result,
+        Some(serde_json::json!({"stdout": "secret contents"}))
+    );
+}
+
+#[test]
+fn test_anonymize_strips_tool_outputs() {
+    let entries = parse_transcript(TRANSCRIPT).unwrap();
+    let anonymized = anonymize(entries, options(true)).unwrap();
+
+    let TranscriptEntry::User(second) = &anonymized[1] else {
+        panic!("expected user entry");
+    };
+    assert_eq!(second.tool_use_result, Some(serde_json::json!(STRIPPED)));
+    let Some(MessageContent::Blocks(blocks)) = second.message.content() else {
+        panic!("expected content blocks");
+    };
+    assert!(matches!(
+        &blocks[0],
+        ContentBlock::ToolResult { content, .. } if content.joined_text() == STRIPPED
+    ));
+}
+
+#[test]
+fn test_anonymize_punctuation_after_home() {
+    let text = "see /Users/alice. or /Users/alice, /Users/alice; [/Users/alice] \
+                </Users/alice> /Users/alice\tx /Users/alice\\src but not /Users/alice.old \
+                /Users/alice_2 /Users/alice-x";
+    let mut line: serde_json::Value =
+        serde_json::from_str(TRANSCRIPT.lines().next().unwrap()).unwrap();
+    line["message"]["content"] = serde_json::json!(text);
+    let entries = parse_transcript(&line.to_string()).unwrap();
+    let anonymized = anonymize(entries, options(false)).unwrap();
+
+    let TranscriptEntry::User(user) = &anonymized[0] else {
+        panic!("expected user entry");
+    };
+    let Some(MessageContent::Text(text)) = user.message.content() else {
+        panic!("expected text content");
+    };
+    assert_eq!(
+        text,
+        "see ~. or ~, ~; [~] <~> ~\tx ~\\src but not /Users/alice.old \
+         /Users/alice_2 /Users/alice-x"
+    );
+}
+
+#[test]
+fn test_anonymize_fails_closed() {
+    // Scrubbing a home directory of "user" turns the entry's `type` tag into
+    // "~", so the result is no longer a valid entry
+    let entries = parse_transcript(TRANSCRIPT).unwrap();
+    let opts = AnonymizeOptions {
+        home_dir: Some("user".to_string()),
+        strip_tool_outputs: false,
+    };
+    assert!(anonymize(entries, opts).is_err());
+}
@@ -0,0 +1,64 @@
+use claude_transcript::schema::{SchemaIssue, validate_for_version};
+use semver::Version;
+use serde_json::json;
+
+fn system_entry() -> serde_json::Value {
+    json!({
+        "type": "system",
+        "uuid": "s1",
+        "timestamp": "2025-01-01T00:00:00Z",
+        "content": "Running hook",
+        "cwd": "/test",
+        "sessionId": "test-session",
+        "version": "1.0.16",
+        "userType": "external",
+        "isSidechain": false,
+        "parentUuid": "a1",
+        "isMeta": false
+    })
+}
+
+#[test]
+fn test_missing_field_required_by_version() {
+    let entries = [system_entry()];
+
+    assert_eq!(
+        validate_for_version(&entries, &Version::new(1, 0, 43)),
+        vec![SchemaIssue {
+            index: 0,
+            entry_type: "system".to_string(),
+            field: "level".to_string(),
+            since: Version::new(1, 0, 35),
+        }]
+    );
+    assert_eq!(
+        validate_for_version(&entries, &Version::new(1, 0, 43))[0].to_string(),
+        "system entry is missing `level` (required since 1.0.35)"
+    );
+
+    // Older versions didn't write `level`
+    assert!(validate_for_version(&entries, &Version::new(1, 0, 16)).is_empty());
+}
+
+#[test]
+fn test_api_errors_need_no_request_id() {
+    let assistant = json!({
+        "type": "assistant",
+        "uuid": "a1",
+        "timestamp": "2025-01-01T00:00:00Z",
+        "message": {},
+        "cwd": "/test",
+        "sessionId": "test-session",
+        "version": "1.0.43",
+        "userType": "external",
+        "isSidechain": false,
+        "parentUuid": "u1"
+    });
+    let mut api_error = assistant.clone();
+    api_error["isApiErrorMessage"] = json!(true);
+
+    let issues = validate_for_version(&[assistant, api_error], &Version::new(1, 0, 43));
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].index, 0);
+    assert_eq!(issues[0].field, "requestId");
+}
@@ -1,5 +1,11 @@
-use claude_transcript::parse::{parse_transcript_line, parse_transcript_with_context};
-use claude_transcript::{ContentBlock, MessageContent, TranscriptEntry, TranscriptMessage};
+use claude_transcript::parse::{
+    parse_and_merge, parse_transcript_iter, parse_transcript_line, parse_transcript_with_context,
+    validate_transcript_streaming,
+};
+use claude_transcript::{
+    ContentBlock, MessageContent, ToolResultContent, TranscriptEntry, TranscriptMessage,
+};
+use std::path::Path;
 
 #[test]
 fn test_tool_result_with_array_content() {
@@ -22,6 +28,28 @@ fn test_tool_result_with_array_content() {
     }
 }
 
+#[test]
+fn test_tool_result_joined_text() {
+    let json_line = r#"{"parentUuid":"9a6212a9-b1d1-4823-b783-0c5248c2602c","isSidechain":false,"userType":"external","cwd":"/test","sessionId":"test-session","version":"1.0.43","type":"user","message":{"role":"user","content":[{"tool_use_id":"toolu_01","type":"tool_result","content":[{"type":"text","text":"first part"},{"type":"text","text":"second part"}]}]},"uuid":"c5c77671-8174-4149-abcc-650cc6058924","timestamp":"2025-07-04T10:59:44.274Z"}"#;
+
+    let entry = parse_transcript_line(json_line).expect("Should parse user entry");
+    let TranscriptEntry::User(user) = entry else {
+        panic!("Expected user entry");
+    };
+    let Some(MessageContent::Blocks(blocks)) = user.message.content() else {
+        panic!("Expected blocks content");
+    };
+    match &blocks[0] {
+        ContentBlock::ToolResult { content, .. } => {
+            assert_eq!(content.joined_text(), "first part\nsecond part");
+        }
+        _ => panic!("Expected tool result block"),
+    }
+
+    let text = ToolResultContent::Text("plain".to_string());
+    assert_eq!(text.joined_text(), "plain");
+}
+
 #[test]
 fn test_parse_various_entry_types() {
     // Test system entry with toolUseID
@@ -84,6 +112,158 @@ invalid json line
     assert_eq!(result.errors[1].line_number, 4); // missing type field
 }
 
+#[test]
+fn test_parse_transcript_iter() {
+    let transcript_data = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"test-uuid","timestamp":"2025-01-01T00:00:00Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}
+
+invalid json line
+{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}
+"#;
+
+    let results: Vec<_> = parse_transcript_iter(transcript_data.as_bytes()).collect();
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], Ok(TranscriptEntry::User(_))));
+    assert!(matches!(results[2], Ok(TranscriptEntry::Summary(_))));
+
+    // Line numbers match the batch parser
+    let err = results[1].as_ref().unwrap_err();
+    let batch = parse_transcript_with_context(transcript_data);
+    assert_eq!(err.line_number, 3);
+    assert_eq!(err.line_number, batch.errors[0].line_number);
+    assert_eq!(err.line_content, "invalid json line");
+}
+
+#[test]
+fn test_parse_transcript_iter_with_offsets() {
+    let user = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"test-uuid","timestamp":"2025-01-01T00:00:00Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}"#;
+    let summary = r#"{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}"#;
+    let transcript_data = format!("{user}\r\n\n{summary}\n");
+
+    let results: Vec<_> = parse_transcript_iter(transcript_data.as_bytes())
+        .with_offsets()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, 0);
+    assert_eq!(results[1].0, user.len() as u64 + 3);
+    assert!(results[0].0 < results[1].0);
+    assert!(matches!(results[1].1, TranscriptEntry::Summary(_)));
+
+    // Each offset points at the start of the entry's line
+    let offset = results[1].0 as usize;
+    assert!(transcript_data[offset..].starts_with(summary));
+}
+
+#[test]
+fn test_parse_result_first_error() {
+    let clean = parse_transcript_with_context(
+        r#"{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}"#,
+    );
+    assert!(clean.is_ok());
+    assert!(clean.first_error().is_none());
+
+    let dirty = parse_transcript_with_context(
+        "{\"type\":\"summary\",\"summary\":\"Test summary\",\"leafUuid\":\"leaf-123\"}\nbad line\nworse line",
+    );
+    assert!(!dirty.is_ok());
+    let error = dirty.first_error().unwrap();
+    assert_eq!(error.line_number, 2);
+    assert_eq!(error.line_content, "bad line");
+}
+
+fn user_line(uuid: &str, timestamp: &str) -> String {
+    format!(
+        r#"{{"type":"user","message":{{"role":"user","content":"hello"}},"uuid":"{uuid}","timestamp":"{timestamp}","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}}"#
+    )
+}
+
+#[test]
+fn test_parse_and_merge() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.jsonl");
+    let second = dir.path().join("second.jsonl");
+    let summary = r#"{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}"#;
+    std::fs::write(
+        &first,
+        [
+            summary.to_string(),
+            user_line("a", "2025-01-01T10:00:00.000Z"),
+            user_line("c", "2025-01-01T10:02:00.000Z"),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    std::fs::write(
+        &second,
+        [
+            // Same instant as 10:01 UTC, in another offset
+            user_line("b", "2025-01-01T12:01:00+02:00"),
+            user_line("d", "2025-01-01T10:03:00.000Z"),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let entries = parse_and_merge(&[&first, &second]).unwrap();
+    let order: Vec<_> = entries
+        .iter()
+        .map(|entry| match entry {
+            TranscriptEntry::User(user) => user.uuid.as_str(),
+            TranscriptEntry::Summary(_) => "summary",
+            _ => "other",
+        })
+        .collect();
+    assert_eq!(order, ["a", "b", "c", "d", "summary"]);
+
+    std::fs::write(&second, "not json").unwrap();
+    let err = parse_and_merge(&[&first, &second]).unwrap_err();
+    assert!(err.to_string().contains("second.jsonl"), "{err}");
+
+    let missing = dir.path().join("missing.jsonl");
+    let err = parse_and_merge(&[&first, &missing]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("missing.jsonl"), "{err}");
+}
+
+#[test]
+fn test_entry_cwd() {
+    let user = r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"test-uuid","timestamp":"2025-01-01T00:00:00Z","cwd":"/home/user/project","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}"#;
+    let entry = parse_transcript_line(user).unwrap();
+    assert_eq!(entry.cwd(), Some(Path::new("/home/user/project")));
+
+    let summary = r#"{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}"#;
+    assert_eq!(parse_transcript_line(summary).unwrap().cwd(), None);
+}
+
+#[test]
+fn test_validate_transcript_streaming() {
+    let transcript_data = r#"not json
+{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}
+{"type":"summary"}
+"#;
+
+    let errors = validate_transcript_streaming(transcript_data.as_bytes());
+    let lines: Vec<_> = errors.iter().map(|e| e.line_number).collect();
+    assert_eq!(lines, [1, 3]);
+    assert_eq!(errors[1].line_content, r#"{"type":"summary"}"#);
+
+    let valid = r#"{"type":"summary","summary":"Test summary","leafUuid":"leaf-123"}"#;
+    assert!(validate_transcript_streaming(valid.as_bytes()).is_empty());
+
+    // A line that isn't UTF-8 is reported without stopping validation
+    let mut data = format!("{valid}\n").into_bytes();
+    data.extend_from_slice(b"{\"type\":\"summary\",\"summary\":\"\xff\xfe\"}\n");
+    data.extend_from_slice(b"not json\n");
+    let errors = validate_transcript_streaming(&data[..]);
+    let lines: Vec<_> = errors.iter().map(|e| e.line_number).collect();
+    assert_eq!(lines, [2, 3]);
+    assert!(
+        errors[0].to_string().contains("invalid utf-8"),
+        "{}",
+        errors[0]
+    );
+}
+
 #[test]
 fn test_complex_content_structures() {
     // Test with complex content array
@@ -237,3 +417,53 @@ fn test_system_entry_without_level() {
         _ => panic!("Expected system entry"),
     }
 }
+
+#[test]
+fn test_system_entry_without_level_or_tool_use_id() {
+    // Usage-limit notices carry neither `level` nor `toolUseID`
+    let json_line = r#"{"type":"system","uuid":"sys-uuid","timestamp":"2025-01-01T00:00:00Z","content":"Claude Opus 4 limit reached, now using Sonnet 4","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"parent-123","isMeta":false}"#;
+
+    let TranscriptEntry::System(system) =
+        parse_transcript_line(json_line).expect("Should parse system entry without level")
+    else {
+        panic!("Expected system entry");
+    };
+    assert_eq!(system.level, None);
+    assert_eq!(system.tool_use_id, None);
+
+    // Missing fields stay absent when serialized again
+    let reserialized = serde_json::to_value(TranscriptEntry::System(system)).unwrap();
+    assert!(reserialized.get("level").is_none());
+    assert!(reserialized.get("toolUseID").is_none());
+}
+
+#[test]
+fn test_synthetic_api_error_roundtrip() {
+    let json_line = r#"{"parentUuid":"p1","isSidechain":false,"userType":"external","cwd":"/test","sessionId":"test-session","version":"1.0.35","type":"assistant","uuid":"a1","timestamp":"2025-07-01T00:00:00.000Z","message":{"id":"msg_1","type":"message","role":"assistant","model":"<synthetic>","content":[{"type":"text","text":"API Error: Request timed out."}],"stop_reason":"stop_sequence","stop_sequence":"","usage":{"input_tokens":0,"output_tokens":0}},"isApiErrorMessage":true}"#;
+
+    let entry = parse_transcript_line(json_line).expect("Should parse synthetic API error");
+    let TranscriptEntry::Assistant(assistant) = &entry else {
+        panic!("Expected assistant entry");
+    };
+    assert!(assistant.request_id.is_none());
+    assert!(matches!(
+        &assistant.message,
+        TranscriptMessage::Assistant { model, .. } if model == "<synthetic>"
+    ));
+
+    // requestId stays absent and the error flag survives serialization
+    let reserialized = serde_json::to_value(&entry).unwrap();
+    assert!(reserialized.get("requestId").is_none());
+    assert_eq!(reserialized["isApiErrorMessage"], true);
+}
+
+#[test]
+fn test_entry_text_from_blocks() {
+    let json_line = r#"{"type":"assistant","message":{"id":"msg_123","type":"message","role":"assistant","model":"test-model","content":[{"type":"thinking","thinking":"hmm"},{"type":"text","text":"Let me check."},{"type":"tool_use","id":"toolu_01","name":"Bash","input":{"command":"ls"}},{"type":"tool_result","tool_use_id":"toolu_01","content":[{"type":"text","text":"Cargo.toml"},{"type":"text","text":"src"}]},{"type":"text","text":"Done."}],"stop_reason":null,"stop_sequence":null,"usage":{"input_tokens":1,"output_tokens":2,"service_tier":"standard"}},"uuid":"test-uuid","timestamp":"2025-01-01T00:00:00Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"parent-123","requestId":"req-123"}"#;
+
+    let entry = parse_transcript_line(json_line).expect("Should parse assistant entry");
+    assert_eq!(
+        entry.text().as_deref(),
+        Some("Let me check.\nCargo.toml\nsrc\nDone.")
+    );
+}
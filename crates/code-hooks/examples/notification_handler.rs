@@ -6,7 +6,9 @@ fn main() -> Result<()> {
 
     // Log notification info to stderr (visible in hooktest output)
     eprintln!("Notification received!");
-    eprintln!("Hook event name: {}", notification.hook_event_name);
+    if let Some(title) = &notification.title {
+        eprintln!("Title: {title}");
+    }
     eprintln!("Message: {}", notification.message);
     eprintln!("Session ID: {}", notification.session_id);
 
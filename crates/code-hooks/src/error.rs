@@ -1,3 +1,5 @@
+use crate::event::EventKind;
+use claude_transcript::parse::TranscriptParseError;
 use std::io;
 use thiserror::Error;
 
@@ -12,9 +14,62 @@ pub enum Error {
     #[error("failed to parse JSON: {0}")]
     JsonParse(#[from] serde_json::Error),
 
+    /// Error parsing hook input, with the input around the failure
+    #[error("failed to parse JSON input at byte {offset}: {source} (near `{snippet}`)")]
+    InputParse {
+        source: serde_json::Error,
+        /// Byte offset of the error in the input
+        offset: usize,
+        /// The input surrounding the error
+        snippet: String,
+    },
+
+    /// Hook input exceeded the size limit, in bytes
+    #[error("input is larger than the {0} byte limit")]
+    InputTooLarge(usize),
+
+    /// A line of the transcript file could not be parsed
+    #[error("{0}")]
+    Transcript(#[from] TranscriptParseError),
+
     /// Invalid exit code provided
     #[error("invalid exit code {0}: codes 0 and 2 are reserved")]
     InvalidExitCode(i32),
+
+    /// Unrecognized hook event name
+    #[error("unknown event type '{0}': expected one of {names}", names = EventKind::names())]
+    UnknownEvent(String),
+}
+
+/// Bytes of context shown on each side of an input parse error
+const SNIPPET_CONTEXT: usize = 20;
+
+impl Error {
+    /// Wrap a parse error with the byte offset and a window of the input
+    /// around where it occurred
+    pub(crate) fn input_parse(source: serde_json::Error, input: &[u8]) -> Self {
+        let offset = error_offset(&source, input);
+        let start = offset.saturating_sub(SNIPPET_CONTEXT);
+        let end = (offset + SNIPPET_CONTEXT).min(input.len());
+        let snippet = String::from_utf8_lossy(&input[start..end])
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        Error::InputParse {
+            source,
+            offset,
+            snippet,
+        }
+    }
+}
+
+/// Convert the 1-based line and column of a parse error into a byte offset
+fn error_offset(error: &serde_json::Error, input: &[u8]) -> usize {
+    let line_start: usize = input
+        .split(|&b| b == b'\n')
+        .take(error.line().saturating_sub(1))
+        .map(|line| line.len() + 1)
+        .sum();
+    (line_start + error.column().saturating_sub(1)).min(input.len())
 }
 
 /// Type alias for Results in this library
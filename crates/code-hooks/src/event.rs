@@ -0,0 +1,106 @@
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The kinds of hook events Claude Code can send.
+///
+/// Each kind has a short name used by command-line tools (e.g. "pretool") and
+/// the `hook_event_name` Claude Code uses in its payloads (e.g. "PreToolUse").
+/// Both forms are accepted when parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// Before a tool is executed
+    PreToolUse,
+    /// After a tool has executed successfully
+    PostToolUse,
+    /// When Claude Code sends a notification
+    Notification,
+    /// When Claude Code finishes responding
+    Stop,
+    /// When a subagent finishes responding
+    SubagentStop,
+    /// When the user submits a prompt, before Claude processes it
+    UserPromptSubmit,
+}
+
+impl EventKind {
+    /// All event kinds, in lifecycle order
+    pub const ALL: [EventKind; 6] = [
+        EventKind::UserPromptSubmit,
+        EventKind::PreToolUse,
+        EventKind::PostToolUse,
+        EventKind::Notification,
+        EventKind::Stop,
+        EventKind::SubagentStop,
+    ];
+
+    /// The short name for this event, as used by command-line tools
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::PreToolUse => "pretool",
+            EventKind::PostToolUse => "posttool",
+            EventKind::Notification => "notification",
+            EventKind::Stop => "stop",
+            EventKind::SubagentStop => "subagentstop",
+            EventKind::UserPromptSubmit => "userpromptsubmit",
+        }
+    }
+
+    /// Comma-separated list of all short event names
+    pub fn names() -> String {
+        EventKind::ALL.map(|kind| kind.name()).join(", ")
+    }
+
+    /// The `hook_event_name` Claude Code sends for this event
+    pub fn hook_event_name(&self) -> &'static str {
+        match self {
+            EventKind::PreToolUse => "PreToolUse",
+            EventKind::PostToolUse => "PostToolUse",
+            EventKind::Notification => "Notification",
+            EventKind::Stop => "Stop",
+            EventKind::SubagentStop => "SubagentStop",
+            EventKind::UserPromptSubmit => "UserPromptSubmit",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s || kind.hook_event_name() == s)
+            .ok_or_else(|| Error::UnknownEvent(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_kind_roundtrip() {
+        for kind in EventKind::ALL {
+            assert_eq!(kind.to_string().parse::<EventKind>().unwrap(), kind);
+            assert_eq!(kind.hook_event_name().parse::<EventKind>().unwrap(), kind);
+        }
+        assert_eq!(
+            "pretool".parse::<EventKind>().unwrap(),
+            EventKind::PreToolUse
+        );
+    }
+
+    #[test]
+    fn test_event_kind_invalid() {
+        let err = "pretooluse".parse::<EventKind>().unwrap_err();
+        assert!(matches!(err, Error::UnknownEvent(ref name) if name == "pretooluse"));
+        assert!(err.to_string().contains("unknown event type 'pretooluse'"));
+    }
+}
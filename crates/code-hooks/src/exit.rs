@@ -0,0 +1,84 @@
+//! Helpers for the exit-code hook protocol.
+//!
+//! Hooks that don't print JSON communicate through their exit status:
+//!
+//! - `0`: success. Stdout is shown to the user in transcript mode.
+//! - `2`: blocking error. Stderr is fed back to Claude to process.
+//! - any other code: non-blocking error. Stderr is shown to the user and
+//!   execution continues.
+//!
+//! Prefer the JSON responses in [`HookResponse`](crate::HookResponse) where
+//! possible: they are better defined and support more behaviors.
+
+use crate::error::{Error, Result};
+use std::convert::Infallible;
+use std::process;
+
+/// Exit code for a blocking error
+const BLOCK: i32 = 2;
+
+/// Exit code used by [`feedback`]
+const FEEDBACK: i32 = 1;
+
+/// Exit with status 0
+pub fn success() -> ! {
+    process::exit(0)
+}
+
+/// Write `message` to stderr and exit with status 2.
+///
+/// Claude Code blocks the action and feeds `message` back to Claude.
+pub fn block_with(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(BLOCK)
+}
+
+/// Write `message` to stderr and exit with status 1.
+///
+/// This is a non-blocking error: the message is shown to the user and
+/// execution continues.
+pub fn feedback(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(FEEDBACK)
+}
+
+/// Write `message` to stderr and exit with a non-blocking error `code`.
+///
+/// Codes 0 and 2 have their own meanings (see [`success`] and [`block_with`]),
+/// so they are rejected with [`Error::InvalidExitCode`] rather than exiting.
+///
+/// ```rust,no_run
+/// # fn main() -> code_hooks::Result<()> {
+/// code_hooks::exit::error(3, "Linter is not installed")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn error(code: i32, message: &str) -> Result<Infallible> {
+    let code = check_error_code(code)?;
+    eprintln!("{message}");
+    process::exit(code)
+}
+
+/// Reject the exit codes reserved for success and blocking
+fn check_error_code(code: i32) -> Result<i32> {
+    match code {
+        0 | BLOCK => Err(Error::InvalidExitCode(code)),
+        code => Ok(code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rejects_reserved_codes() {
+        assert!(matches!(error(0, "ok"), Err(Error::InvalidExitCode(0))));
+        assert!(matches!(
+            error(2, "blocked"),
+            Err(Error::InvalidExitCode(2))
+        ));
+        assert_eq!(check_error_code(FEEDBACK).unwrap(), 1);
+        assert_eq!(check_error_code(3).unwrap(), 3);
+    }
+}
@@ -1,8 +1,18 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use claude_transcript::TranscriptEntry;
+use claude_transcript::analysis::filter_sidechain;
+use claude_transcript::parse::parse_transcript_with_context;
+use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Read};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
 use std::process;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Trait for hook input types that can be read from stdin.
 ///
@@ -20,15 +30,92 @@ use std::process;
 /// println!("Tool name: {}", input.tool_name);
 /// ```
 pub trait Input: for<'de> Deserialize<'de> + Sized {
-    /// Read and parse input from stdin.
+    /// Read and parse input from stdin, failing with [`Error::InputTooLarge`]
+    /// if it's over [`DEFAULT_INPUT_LIMIT`] bytes.
     fn read() -> Result<Self> {
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        let parsed = serde_json::from_str(&buffer)?;
-        Ok(parsed)
+        Self::read_with_limit(DEFAULT_INPUT_LIMIT)
     }
+
+    /// Read and parse input from stdin, failing with [`Error::InputTooLarge`]
+    /// if it's over `max_bytes`.
+    fn read_with_limit(max_bytes: usize) -> Result<Self> {
+        Self::read_from_with_limit(io::stdin(), max_bytes)
+    }
+
+    /// Read and parse input from an arbitrary reader.
+    ///
+    /// This is useful for testing hooks without spawning a process:
+    ///
+    /// ```rust
+    /// use code_hooks::{Input, PreToolUse};
+    /// use std::io::Cursor;
+    ///
+    /// let json = r#"{"session_id":"s","transcript_path":"/t","tool_name":"Bash","tool_input":{}}"#;
+    /// let input = PreToolUse::read_from(Cursor::new(json)).unwrap();
+    /// assert_eq!(input.tool_name, "Bash");
+    /// ```
+    ///
+    /// The input is read as raw bytes, so invalid UTF-8 is reported as a JSON
+    /// error rather than an IO error. Trailing whitespace is ignored. Input
+    /// over [`DEFAULT_INPUT_LIMIT`] bytes is rejected.
+    fn read_from<R: Read>(reader: R) -> Result<Self> {
+        Self::read_from_with_limit(reader, DEFAULT_INPUT_LIMIT)
+    }
+
+    /// Read and parse input from an arbitrary reader, failing with
+    /// [`Error::InputTooLarge`] if it's over `max_bytes`.
+    ///
+    /// At most `max_bytes + 1` bytes are read, so oversized input is never
+    /// held in memory.
+    fn read_from_with_limit<R: Read>(reader: R, max_bytes: usize) -> Result<Self> {
+        let mut buffer = Vec::new();
+        reader.take(max_bytes as u64 + 1).read_to_end(&mut buffer)?;
+        parse_input(&buffer, max_bytes)
+    }
+
+    /// Read and parse input from tokio's stdin without blocking the runtime,
+    /// failing with [`Error::InputTooLarge`] if it's over
+    /// [`DEFAULT_INPUT_LIMIT`] bytes.
+    #[cfg(feature = "async")]
+    fn read_async() -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: Send,
+    {
+        Self::read_from_async(tokio::io::stdin())
+    }
+
+    /// Read and parse input from an async reader, with the same size limit
+    /// as [`Input::read_async`].
+    #[cfg(feature = "async")]
+    fn read_from_async<R>(reader: R) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: Send,
+        R: tokio::io::AsyncRead + Unpin + Send,
+    {
+        use tokio::io::AsyncReadExt;
+
+        async move {
+            let mut buffer = Vec::new();
+            reader
+                .take(DEFAULT_INPUT_LIMIT as u64 + 1)
+                .read_to_end(&mut buffer)
+                .await?;
+            parse_input(&buffer, DEFAULT_INPUT_LIMIT)
+        }
+    }
+}
+
+/// Parse input read with a limit of `max_bytes + 1`
+fn parse_input<T: Input>(buffer: &[u8], max_bytes: usize) -> Result<T> {
+    if buffer.len() > max_bytes {
+        return Err(Error::InputTooLarge(max_bytes));
+    }
+    serde_json::from_slice(buffer).map_err(|e| Error::input_parse(e, buffer))
 }
 
+/// Largest hook input [`Input::read`] accepts, in bytes (16 MiB)
+pub const DEFAULT_INPUT_LIMIT: usize = 16 * 1024 * 1024;
+
 /// Trait for hook response types that can be serialized and sent to stdout.
 ///
 /// This trait provides a standard way to respond from Claude Code hooks by:
@@ -46,38 +133,182 @@ pub trait Input: for<'de> Deserialize<'de> + Sized {
 /// ```
 pub trait HookResponse: Serialize {
     /// Serialize the response to JSON, print to stdout, and exit with status 0.
+    ///
+    /// Exits with status 1 if the response can't be serialized or written.
     fn respond(self) -> !
     where
         Self: Sized,
     {
-        match serde_json::to_string(&self) {
-            Ok(json) => {
-                println!("{json}");
-                process::exit(0);
-            }
-            Err(e) => {
-                eprintln!("Failed to serialize response: {e}");
-                process::exit(1);
-            }
+        print_and_exit(self.try_respond())
+    }
+
+    /// Like [`respond`](Self::respond), but prints indented multi-line JSON
+    /// for reading hook output while debugging.
+    ///
+    /// Claude Code accepts either form.
+    fn respond_pretty(self) -> !
+    where
+        Self: Sized,
+    {
+        print_and_exit(self.try_respond_pretty())
+    }
+
+    /// Serialize the response to the JSON [`respond`](Self::respond) would
+    /// print, without printing or exiting.
+    fn try_respond(self) -> Result<String>
+    where
+        Self: Sized,
+    {
+        Ok(serde_json::to_string(&self)?)
+    }
+
+    /// Serialize the response to the indented JSON
+    /// [`respond_pretty`](Self::respond_pretty) would print.
+    fn try_respond_pretty(self) -> Result<String>
+    where
+        Self: Sized,
+    {
+        Ok(serde_json::to_string_pretty(&self)?)
+    }
+
+    /// Serialize the response to JSON and write it to `writer`, followed by a newline.
+    ///
+    /// This is the non-exiting counterpart of [`respond`](Self::respond), for
+    /// composing hooks or capturing output in tests.
+    fn respond_to<W: Write>(self, writer: &mut W) -> Result<()>
+    where
+        Self: Sized,
+    {
+        writeln!(writer, "{}", self.try_respond()?)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Append the response to a JSONL audit log, then respond as [`respond`](Self::respond).
+    ///
+    /// If the log can't be written, a warning is printed to stderr and the
+    /// hook still responds.
+    fn respond_and_log(self, logfile: &Path) -> !
+    where
+        Self: Sized,
+    {
+        if let Err(e) = append_log(&self, logfile) {
+            eprintln!("Failed to log response to {}: {e}", logfile.display());
+        }
+        self.respond()
+    }
+
+    /// Append the response to a JSONL audit log, then write it to `out`.
+    ///
+    /// This is the non-exiting counterpart of [`respond_and_log`](Self::respond_and_log).
+    /// Each log line is an object with a `timestamp` (seconds since the Unix
+    /// epoch) and the `output` sent.
+    fn respond_and_log_to<W: Write>(self, out: &mut W, logfile: &Path) -> Result<()>
+    where
+        Self: Sized,
+    {
+        append_log(&self, logfile)?;
+        self.respond_to(out)
+    }
+}
+
+/// Print a serialized response and exit with status 0, or report the error
+/// and exit with status 1
+fn print_and_exit(json: Result<String>) -> ! {
+    match json {
+        Ok(json) => {
+            println!("{json}");
+            process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to serialize response: {e}");
+            process::exit(1);
         }
     }
 }
 
+/// Report an internal hook error without affecting Claude: print `message` to
+/// stderr and a passthrough response to stdout, then exit with status 0.
+///
+/// Use this instead of returning an error from `main` when a hook failure
+/// shouldn't block the action or be shown to Claude.
+///
+/// ```rust,no_run
+/// use code_hooks::{Input, PreToolUse, respond_error};
+///
+/// let input = PreToolUse::read().unwrap_or_else(|e| respond_error(&e.to_string()));
+/// ```
+pub fn respond_error(message: &str) -> ! {
+    // Write errors are ignored: the hook should exit cleanly regardless
+    let _ = respond_error_to(message, &mut io::stdout(), &mut io::stderr());
+    process::exit(0)
+}
+
+/// Write the output of [`respond_error`] to `out` and `err` without exiting.
+///
+/// The passthrough response is an empty JSON object, which every event
+/// accepts.
+pub fn respond_error_to<W: Write, E: Write>(message: &str, out: &mut W, err: &mut E) -> Result<()> {
+    writeln!(err, "Hook error: {message}")?;
+    writeln!(out, "{{}}")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Append a response with a timestamp to a JSONL log file
+fn append_log<T: Serialize + ?Sized>(response: &T, logfile: &Path) -> Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let line = serde_json::json!({
+        "timestamp": timestamp,
+        "output": response,
+    });
+    let mut file = OpenOptions::new().create(true).append(true).open(logfile)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// Get a string value from a tool input map, if present
+pub(crate) fn input_str<'a>(tool_input: &'a HashMap<String, Value>, key: &str) -> Option<&'a str> {
+    tool_input.get(key).and_then(|v| v.as_str())
+}
+
 /// Helper function for serde to skip serializing None values
 pub(crate) fn is_none<T>(opt: &Option<T>) -> bool {
     opt.is_none()
 }
 
-/// Decision type for approve/block operations.
+/// Decision type for approve/block/ask operations.
 ///
 /// Used in PreToolUse, PostToolUse, and Stop hooks to control execution flow.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum Decision {
     /// Approve the operation (PreToolUse only - bypasses permission system)
+    ///
+    /// `"allow"`, as written by some other tools, is also accepted.
+    #[serde(alias = "allow")]
     Approve,
     /// Block the operation and provide feedback to Claude
     Block,
+    /// Ask the user to confirm the operation (PreToolUse only)
+    Ask,
+}
+
+/// Metadata attached to a hook response for logging and auditing.
+///
+/// Claude Code ignores this object. It lets tools that read hook output (like
+/// hooktest) correlate a decision with the rule that produced it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct HookMetadata {
+    /// Machine-readable identifier of the rule that produced the decision
+    #[serde(skip_serializing_if = "is_none")]
+    pub rule_id: Option<String>,
 }
 
 /// Trait for hook input types that can read their associated transcript file.
@@ -85,8 +316,453 @@ pub enum Decision {
 /// This trait provides a standard way to read and parse the transcript file
 /// referenced in the hook input's transcript_path field.
 pub trait TranscriptReader {
+    /// Path to the conversation transcript file
+    fn transcript_path(&self) -> &str;
+
     /// Read and parse the transcript file.
     ///
     /// Returns a vector of transcript entries from the JSONL file at transcript_path.
-    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>>;
+    /// If any line fails to parse, returns [`Error::Transcript`] for the first
+    /// bad line, with its line number and content. Gzip-compressed transcripts
+    /// (e.g. archived `.jsonl.gz` files) are decompressed transparently.
+    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
+        let content = read_transcript_file(Path::new(self.transcript_path()))?;
+        let result = parse_transcript_with_context(&content);
+        match result.errors.into_iter().next() {
+            Some(error) => Err(error.into()),
+            None => Ok(result.entries),
+        }
+    }
+
+    /// Read and parse the transcript file, tolerating a truncated last line.
+    ///
+    /// Claude Code may still be appending to the transcript when a hook reads
+    /// it, so a malformed final line is dropped. Malformed earlier lines are
+    /// still reported as in [`read_transcript`](Self::read_transcript).
+    fn read_transcript_lenient(&self) -> Result<Vec<TranscriptEntry>> {
+        let content = read_transcript_file(Path::new(self.transcript_path()))?;
+        let last_line = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .last()
+            .map(|(idx, _)| idx + 1);
+        let result = parse_transcript_with_context(&content);
+        match result
+            .errors
+            .into_iter()
+            .find(|error| Some(error.line_number) != last_line)
+        {
+            Some(error) => Err(error.into()),
+            None => Ok(result.entries),
+        }
+    }
+
+    /// Read the transcript, keeping only the subagent entries (those with
+    /// `is_sidechain` set), e.g. to see a subagent's own turns in a
+    /// `SubagentStop` hook.
+    fn read_sidechain_transcript(&self) -> Result<Vec<TranscriptEntry>> {
+        Ok(filter_sidechain(self.read_transcript()?, true))
+    }
+}
+
+/// Magic bytes at the start of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Read a transcript file as text, decompressing it if it is gzipped
+fn read_transcript_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut content = String::new();
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+}
+
+/// Wrapper around a [`TranscriptReader`] that parses the transcript once and
+/// reuses the entries until the file changes.
+///
+/// Entries are cached by path and modification time, so repeated calls to
+/// [`read_transcript`](Self::read_transcript) only re-read the file after it
+/// has been written to.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use code_hooks::{CachedTranscriptReader, Input, Stop};
+///
+/// let input = CachedTranscriptReader::new(Stop::read()?);
+/// let entries = input.read_transcript()?;
+/// // Served from the cache
+/// let again = input.read_transcript()?;
+/// # Ok::<(), code_hooks::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct CachedTranscriptReader<T> {
+    inner: T,
+    cache: RefCell<Option<CachedTranscript>>,
+}
+
+#[derive(Debug)]
+struct CachedTranscript {
+    path: String,
+    modified: SystemTime,
+    entries: Rc<Vec<TranscriptEntry>>,
+}
+
+impl<T: TranscriptReader> CachedTranscriptReader<T> {
+    /// Wrap a transcript reader, typically a hook input
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            cache: RefCell::new(None),
+        }
+    }
+
+    /// The wrapped reader
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the reader, discarding the cache
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read and parse the transcript, reusing the cached entries if the file
+    /// is unchanged since the last call
+    pub fn read_transcript(&self) -> Result<Rc<Vec<TranscriptEntry>>> {
+        let path = self.inner.transcript_path();
+        let modified = fs::metadata(path)?.modified()?;
+
+        if let Some(cached) = self.cache.borrow().as_ref()
+            && cached.path == path
+            && cached.modified == modified
+        {
+            return Ok(Rc::clone(&cached.entries));
+        }
+
+        let entries = Rc::new(self.inner.read_transcript()?);
+        *self.cache.borrow_mut() = Some(CachedTranscript {
+            path: path.to_string(),
+            modified,
+            entries: Rc::clone(&entries),
+        });
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claude_transcript::parse::parse_transcript;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    /// Reader that counts how many times the transcript is parsed
+    struct CountingReader {
+        path: String,
+        reads: Cell<usize>,
+    }
+
+    impl TranscriptReader for CountingReader {
+        fn transcript_path(&self) -> &str {
+            &self.path
+        }
+
+        fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
+            self.reads.set(self.reads.get() + 1);
+            let content = fs::read_to_string(&self.path)?;
+            Ok(parse_transcript(&content)?)
+        }
+    }
+
+    #[test]
+    fn test_decision_serialization() {
+        for (decision, name) in [
+            (Decision::Approve, "approve"),
+            (Decision::Block, "block"),
+            (Decision::Ask, "ask"),
+        ] {
+            let json = serde_json::to_string(&decision).unwrap();
+            assert_eq!(json, format!("\"{name}\""));
+            assert_eq!(serde_json::from_str::<Decision>(&json).unwrap(), decision);
+        }
+    }
+
+    #[test]
+    fn test_decision_allow_alias() {
+        let decision: Decision = serde_json::from_str(r#""allow""#).unwrap();
+        assert_eq!(decision, Decision::Approve);
+        assert_eq!(serde_json::to_string(&decision).unwrap(), r#""approve""#);
+    }
+
+    #[test]
+    fn test_read_from_with_limit() {
+        let json =
+            r#"{"session_id":"s","transcript_path":"/t","tool_name":"Bash","tool_input":{}}"#;
+        let input = crate::PreToolUse::read_from_with_limit(json.as_bytes(), json.len()).unwrap();
+        assert_eq!(input.tool_name, "Bash");
+
+        let err =
+            crate::PreToolUse::read_from_with_limit(json.as_bytes(), json.len() - 1).unwrap_err();
+        assert!(matches!(err, Error::InputTooLarge(limit) if limit == json.len() - 1));
+        assert_eq!(
+            err.to_string(),
+            format!("input is larger than the {} byte limit", json.len() - 1)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_read_from_async() {
+        let json = r#"{"session_id":"s","transcript_path":"/t","stop_hook_active":true}"#;
+        let input = crate::Stop::read_from_async(json.as_bytes()).await.unwrap();
+        assert_eq!(input.session_id, "s");
+        assert!(input.stop_hook_active);
+    }
+
+    #[test]
+    fn test_read_from_malformed_input_snippet() {
+        let input = "{\n  \"tool_name\": \"Bash\",\n  \"tool_input\": {\"command\": \"ls\",}\n}";
+        let err = crate::PreToolUse::read_from(input.as_bytes()).unwrap_err();
+
+        let Error::InputParse {
+            offset, snippet, ..
+        } = &err
+        else {
+            panic!("expected InputParse, got {err:?}");
+        };
+        assert_eq!(&input[*offset..*offset + 1], "}");
+        assert!(snippet.contains(r#""ls",}"#), "snippet: {snippet}");
+        assert!(
+            err.to_string()
+                .starts_with(&format!("failed to parse JSON input at byte {offset}:"))
+        );
+    }
+
+    #[test]
+    fn test_read_gzipped_transcript() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let transcript = tempfile::Builder::new()
+            .suffix(".jsonl.gz")
+            .tempfile()
+            .unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(
+                b"{\"type\":\"summary\",\"summary\":\"one\",\"leafUuid\":\"l1\"}\n{\"type\":\"summary\",\"summary\":\"two\",\"leafUuid\":\"l2\"}\n",
+            )
+            .unwrap();
+        fs::write(transcript.path(), encoder.finish().unwrap()).unwrap();
+
+        let input = crate::Stop {
+            session_id: "s".to_string(),
+            transcript_path: transcript.path().to_string_lossy().to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+        let entries = input.read_transcript().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(&entries[1], TranscriptEntry::Summary(s) if s.summary == "two"));
+    }
+
+    #[test]
+    fn test_read_transcript_lenient() {
+        let transcript = tempfile::NamedTempFile::new().unwrap();
+        let input = crate::Stop {
+            session_id: "s".to_string(),
+            transcript_path: transcript.path().to_string_lossy().to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+
+        fs::write(
+            transcript.path(),
+            "{\"type\":\"summary\",\"summary\":\"one\",\"leafUuid\":\"l1\"}\n{\"type\":\"summary\",\"summary\":\"two\",\"leafUuid\":\"l2\"}\n{\"type\":\"summ",
+        )
+        .unwrap();
+        let entries = input.read_transcript_lenient().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(&entries[1], TranscriptEntry::Summary(s) if s.summary == "two"));
+        assert!(input.read_transcript().is_err());
+
+        // Earlier bad lines are still errors
+        fs::write(
+            transcript.path(),
+            "{\"type\":\"summ\n{\"type\":\"summary\",\"summary\":\"two\",\"leafUuid\":\"l2\"}\n",
+        )
+        .unwrap();
+        let err = input.read_transcript_lenient().unwrap_err();
+        assert!(matches!(&err, Error::Transcript(e) if e.line_number == 1));
+    }
+
+    #[test]
+    fn test_read_sidechain_transcript() {
+        let line = |uuid: &str, sidechain: bool| {
+            format!(
+                r#"{{"type":"user","message":{{"role":"user","content":"hello"}},"uuid":"{uuid}","timestamp":"2025-01-01T00:00:00Z","cwd":"/test","sessionId":"s","version":"1.0.0","userType":"external","isSidechain":{sidechain},"parentUuid":null}}"#
+            )
+        };
+        let transcript = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            transcript.path(),
+            [line("main", false), line("sub", true)].join("\n"),
+        )
+        .unwrap();
+        let input = crate::SubagentStop {
+            session_id: "s".to_string(),
+            transcript_path: transcript.path().to_string_lossy().to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+
+        let entries = input.read_sidechain_transcript().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(&entries[0], TranscriptEntry::User(user) if user.uuid == "sub"));
+        assert_eq!(input.read_transcript().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_transcript_bad_line() {
+        let transcript = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            transcript.path(),
+            "{\"type\":\"summary\",\"summary\":\"ok\",\"leafUuid\":\"l1\"}\n{\"type\":\"summary\",\"summary\":\n",
+        )
+        .unwrap();
+        let input = crate::Stop {
+            session_id: "s".to_string(),
+            transcript_path: transcript.path().to_string_lossy().to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+
+        let err = input.read_transcript().unwrap_err();
+        let Error::Transcript(parse_error) = &err else {
+            panic!("expected Transcript, got {err:?}");
+        };
+        assert_eq!(parse_error.line_number, 2);
+        assert_eq!(
+            parse_error.line_content,
+            "{\"type\":\"summary\",\"summary\":"
+        );
+        assert!(
+            err.to_string()
+                .starts_with("Failed to parse transcript at line 2:")
+        );
+    }
+
+    #[test]
+    fn test_try_respond() {
+        let json = crate::PreToolUseOutput::approve("Safe")
+            .try_respond()
+            .unwrap();
+        assert_eq!(json, r#"{"decision":"approve","reason":"Safe"}"#);
+    }
+
+    #[test]
+    fn test_try_respond_pretty() {
+        let json = crate::PreToolUseOutput::approve("Safe")
+            .try_respond_pretty()
+            .unwrap();
+        assert!(json.contains('\n'), "not multi-line: {json}");
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap(),
+            serde_json::json!({"decision": "approve", "reason": "Safe"})
+        );
+    }
+
+    #[test]
+    fn test_respond_to() {
+        let mut out = Vec::new();
+        crate::PreToolUseOutput::approve("Safe")
+            .respond_to(&mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"decision\":\"approve\",\"reason\":\"Safe\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_respond_error_to() {
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        respond_error_to("config file missing", &mut out, &mut err).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, "{}\n");
+        let response: crate::PreToolUseOutput = serde_json::from_str(&out).unwrap();
+        assert_eq!(response.decision, None);
+        assert_eq!(
+            String::from_utf8(err).unwrap(),
+            "Hook error: config file missing\n"
+        );
+    }
+
+    #[test]
+    fn test_respond_and_log_to() {
+        let log = tempfile::NamedTempFile::new().unwrap();
+        let mut out = Vec::new();
+
+        crate::PreToolUseOutput::block("Dangerous")
+            .respond_and_log_to(&mut out, log.path())
+            .unwrap();
+        crate::PreToolUseOutput::passthrough()
+            .respond_and_log_to(&mut Vec::new(), log.path())
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"decision\":\"block\",\"reason\":\"Dangerous\"}\n"
+        );
+
+        let logged = fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<Value> = logged
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0]["timestamp"].as_u64().unwrap() > 0);
+        assert_eq!(
+            lines[0]["output"],
+            serde_json::json!({"decision": "block", "reason": "Dangerous"})
+        );
+        assert_eq!(lines[1]["output"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_cached_transcript_reader() {
+        let line = r#"{"type":"summary","summary":"Test","leafUuid":"u1"}"#;
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), line).unwrap();
+
+        let reader = CachedTranscriptReader::new(CountingReader {
+            path: file.path().to_str().unwrap().to_string(),
+            reads: Cell::new(0),
+        });
+
+        // Unchanged file is read once
+        let first = reader.read_transcript().unwrap();
+        let second = reader.read_transcript().unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!(reader.inner().reads.get(), 1);
+
+        // A modified file invalidates the cache
+        fs::write(file.path(), format!("{line}\n{line}")).unwrap();
+        let later = SystemTime::now() + Duration::from_secs(10);
+        fs::File::options()
+            .write(true)
+            .open(file.path())
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let third = reader.read_transcript().unwrap();
+        assert_eq!(third.len(), 2);
+        assert_eq!(reader.inner().reads.get(), 2);
+    }
 }
@@ -5,9 +5,10 @@
 //! behavior, ensuring certain actions always happen rather than relying on the LLM
 //! to choose to run them.
 //!
-//! This library implements the JSON-based hook protocol used by Claude Code only, avoiding the
-//! less well-defined error code protocol. This means code-hooks tools always exit with status code
-//! 0, and return well-formed JSON responses.
+//! This library is built around the JSON-based hook protocol used by Claude Code, rather than the
+//! less well-defined error code protocol. Responses sent with [`HookResponse`] always exit with
+//! status code 0 and return well-formed JSON. For hooks configured for the exit code protocol, the
+//! [`exit`] module provides helpers that exit with the appropriate codes.
 //!
 //! # Example
 //!
@@ -17,30 +18,77 @@
 //! fn main() -> Result<()> {
 //!     let input = PreToolUse::read()?;
 //!     
-//!     if input.tool_name == "Bash" {
-//!         if let Some(command) = input.tool_input.get("command").and_then(|v| v.as_str()) {
-//!             if command.contains("rm -rf") {
-//!                 PreToolUseOutput::block("Dangerous command detected").respond();
-//!             }
+//!     if let Some(command) = input.bash_command() {
+//!         if command.contains("rm -rf") {
+//!             PreToolUseOutput::block("Dangerous command detected").respond();
 //!         }
 //!     }
 //!     
 //!     PreToolUseOutput::approve("Command validated").respond();
 //! }
 //! ```
+//!
+//! # Transcripts
+//!
+//! Transcript types come from the `claude-transcript` crate, which is
+//! canonical, and are re-exported as [`transcript`]. Entries returned by
+//! [`TranscriptReader`] are `claude_transcript` types, so there is nothing to
+//! convert when using both crates.
 
 mod error;
+mod event;
+pub mod exit;
 mod io;
 mod notification;
 mod posttool;
 mod pretool;
+#[cfg(feature = "schemars")]
+mod schema;
 mod stop;
 mod subagent_stop;
+mod tools;
+mod user_prompt_submit;
 
+pub use claude_transcript as transcript;
 pub use error::{Error, Result};
-pub use io::{Decision, HookResponse, Input, TranscriptReader};
+pub use event::EventKind;
+pub use io::{
+    CachedTranscriptReader, DEFAULT_INPUT_LIMIT, Decision, HookMetadata, HookResponse, Input,
+    TranscriptReader, respond_error, respond_error_to,
+};
 pub use notification::{Notification, NotificationOutput};
-pub use posttool::{PostToolUse, PostToolUseOutput};
-pub use pretool::{PreToolUse, PreToolUseOutput};
+pub use posttool::{PostToolUse, PostToolUseBuilder, PostToolUseOutput};
+pub use pretool::{
+    PermissionDecision, PermissionMode, PreToolUse, PreToolUseBuilder, PreToolUseChecker,
+    PreToolUseHookSpecificOutput, PreToolUseOutput,
+};
+#[cfg(feature = "schemars")]
+pub use schema::{input_schema, output_schema};
 pub use stop::{Stop, StopOutput};
 pub use subagent_stop::{SubagentStop, SubagentStopOutput};
+pub use tools::{
+    EditOp, ToolInput, ToolName, WebFetchInput, WebFetchResponse, WebSearchInput,
+    WebSearchResponse, WebSearchResult, path_matches, redact_tool_input,
+};
+pub use user_prompt_submit::{
+    UserPromptSubmit, UserPromptSubmitHookSpecificOutput, UserPromptSubmitOutput,
+};
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_transcript_types_are_claude_transcript() {
+        let line = r#"{"type":"assistant","message":{"id":"msg_1","type":"message","role":"assistant","model":"test-model","content":[{"type":"thinking","thinking":"hmm"},{"type":"text","text":"hi"}],"stop_reason":"end_turn","stop_sequence":null,"usage":{"input_tokens":1,"output_tokens":1}},"uuid":"a1","timestamp":"2025-01-01T00:00:01Z","cwd":"/test","sessionId":"s","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}"#;
+        let entry: crate::transcript::TranscriptEntry =
+            crate::transcript::parse::parse_transcript_line(line).unwrap();
+
+        // The re-export is the same type, so no conversion is needed
+        let entry: claude_transcript::TranscriptEntry = entry;
+        let claude_transcript::TranscriptEntry::Assistant(assistant) = entry else {
+            panic!("expected an assistant entry");
+        };
+        assert_eq!(assistant.uuid, "a1");
+        let text = assistant.message.content().and_then(|c| c.as_text());
+        assert_eq!(text.as_deref(), Some("hi"));
+    }
+}
@@ -1,10 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
 
-use crate::Result;
 use crate::io::{HookResponse, Input, TranscriptReader, is_none};
-use claude_transcript::TranscriptEntry;
-use claude_transcript::parse::parse_transcript;
 
 /// Input structure for Notification hooks.
 ///
@@ -12,6 +8,7 @@ use claude_transcript::parse::parse_transcript;
 /// you to customize how you receive alerts (e.g., when Claude needs input
 /// or permission to run something).
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct Notification {
     /// Unique identifier for the current Claude Code session
@@ -20,11 +17,16 @@ pub struct Notification {
     /// Path to the conversation transcript JSON file
     pub transcript_path: String,
 
+    /// Name of the event, "Notification". Older Claude Code versions don't send it.
+    #[serde(default, skip_serializing_if = "is_none", alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
+
     /// The notification message content
     pub message: String,
 
-    /// The notification title (typically "Claude Code")
-    pub hook_event_name: String,
+    /// The notification title (typically "Claude Code"), if one was sent
+    #[serde(default, skip_serializing_if = "is_none")]
+    pub title: Option<String>,
 }
 
 impl Notification {
@@ -35,6 +37,15 @@ impl Notification {
         NotificationOutput::default()
     }
 
+    /// Create a response that handles the notification normally but hides this
+    /// hook's output from transcript mode
+    ///
+    /// Unlike [`passthrough`](Self::passthrough), which leaves the output
+    /// visible, this sets `suppressOutput`.
+    pub fn silent() -> NotificationOutput {
+        NotificationOutput::default().and_suppress_output(true)
+    }
+
     /// Create a response that stops Claude from continuing
     ///
     /// This prevents Claude from continuing after the notification.
@@ -46,9 +57,8 @@ impl Notification {
 impl Input for Notification {}
 
 impl TranscriptReader for Notification {
-    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
-        let content = fs::read_to_string(&self.transcript_path)?;
-        Ok(parse_transcript(&content)?)
+    fn transcript_path(&self) -> &str {
+        &self.transcript_path
     }
 }
 
@@ -56,6 +66,7 @@ impl TranscriptReader for Notification {
 ///
 /// Controls continuation and output visibility for notification handling.
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotificationOutput {
     /// Whether Claude should continue after hook execution (default: true)
@@ -79,6 +90,12 @@ impl NotificationOutput {
         self
     }
 
+    /// Stop Claude from continuing after this hook, showing `reason` to the
+    /// user. An alias for [`and_stop`](Self::and_stop).
+    pub fn and_block_processing(self, reason: &str) -> Self {
+        self.and_stop(reason)
+    }
+
     /// Set whether to suppress output in transcript mode
     pub fn and_suppress_output(mut self, suppress: bool) -> Self {
         self.suppress_output = Some(suppress);
@@ -98,8 +115,9 @@ mod tests {
         let notification = Notification {
             session_id: "test-session".to_string(),
             transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
             message: "Claude needs permission to run a command".to_string(),
-            hook_event_name: "Claude Code".to_string(),
+            title: Some("Claude Code".to_string()),
         };
 
         // Test passthrough response
@@ -117,4 +135,46 @@ mod tests {
             Some("User intervention required".to_string())
         );
     }
+
+    #[test]
+    fn test_notification_payload_roundtrip() {
+        let payload = serde_json::json!({
+            "session_id": "abc123",
+            "transcript_path": "/Users/me/.claude/projects/my-project/abc123.jsonl",
+            "hook_event_name": "Notification",
+            "message": "Claude needs your permission to use Bash",
+            "title": "Claude Code"
+        });
+        let notification: Notification = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(
+            notification.hook_event_name.as_deref(),
+            Some("Notification")
+        );
+        assert_eq!(
+            notification.message,
+            "Claude needs your permission to use Bash"
+        );
+        assert_eq!(notification.title.as_deref(), Some("Claude Code"));
+        assert_eq!(serde_json::to_value(&notification).unwrap(), payload);
+
+        // Older payloads carry only the message
+        let notification: Notification = serde_json::from_str(
+            r#"{"session_id":"s","transcript_path":"/t","message":"Claude is waiting for your input"}"#,
+        )
+        .unwrap();
+        assert_eq!(notification.hook_event_name, None);
+        assert_eq!(notification.title, None);
+    }
+
+    #[test]
+    fn test_silent_output() {
+        assert_eq!(
+            serde_json::to_value(Notification::silent()).unwrap(),
+            serde_json::json!({"suppressOutput": true})
+        );
+        assert_eq!(
+            serde_json::to_value(Notification::passthrough()).unwrap(),
+            serde_json::json!({})
+        );
+    }
 }
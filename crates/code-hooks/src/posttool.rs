@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::fs;
 
-use crate::Result;
-use crate::io::{Decision, HookResponse, Input, TranscriptReader, is_none};
-use claude_transcript::TranscriptEntry;
-use claude_transcript::parse::parse_transcript;
+use crate::io::{
+    Decision, HookMetadata, HookResponse, Input, TranscriptReader, input_str, is_none,
+};
+use crate::tools::{
+    EditOp, ToolName, WebFetchResponse, WebSearchResponse, multi_edits, web_fetch_response,
+    web_search_response,
+};
 
 /// Input structure for PostToolUse hooks.
 ///
@@ -14,12 +16,16 @@ use claude_transcript::parse::parse_transcript;
 /// They can provide feedback to Claude but cannot prevent the tool from running
 /// (since it already ran).
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct PostToolUse {
     /// Unique identifier for the current Claude Code session
     pub session_id: String,
     /// Path to the conversation transcript JSON file
     pub transcript_path: String,
+    /// Name of the event, e.g. "PostToolUse". Older Claude Code versions don't send it.
+    #[serde(default, skip_serializing_if = "is_none", alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
     /// Name of the tool that was called
     pub tool_name: String,
     /// Tool-specific input parameters that were used
@@ -29,6 +35,11 @@ pub struct PostToolUse {
 }
 
 impl PostToolUse {
+    /// Start building a PostToolUse, e.g. to test a hook without writing JSON
+    pub fn builder() -> PostToolUseBuilder {
+        PostToolUseBuilder::default()
+    }
+
     /// Create a block response that suppresses the normal tool result
     ///
     /// Claude sees the reason as an error message instead of the actual tool output.
@@ -42,14 +53,124 @@ impl PostToolUse {
     pub fn passthrough(&self) -> PostToolUseOutput {
         PostToolUseOutput::passthrough()
     }
+
+    /// The tool being called, for matching without comparing strings
+    pub fn tool(&self) -> ToolName {
+        let Ok(tool) = self.tool_name.parse();
+        tool
+    }
+
+    /// Get this input only if it's a call to the tool `name`, so other tools
+    /// can be passed through early:
+    ///
+    /// ```rust,no_run
+    /// use code_hooks::{HookResponse, Input, PostToolUse, PostToolUseOutput};
+    ///
+    /// let input = PostToolUse::read().unwrap();
+    /// let Some(input) = input.require_tool("Edit") else {
+    ///     PostToolUseOutput::passthrough().respond()
+    /// };
+    /// ```
+    pub fn require_tool(&self, name: &str) -> Option<&Self> {
+        (self.tool_name == name).then_some(self)
+    }
+
+    /// Get the `command` input of a Bash tool call
+    pub fn bash_command(&self) -> Option<&str> {
+        input_str(&self.tool_input, "command")
+    }
+
+    /// Get the `file_path` input of a file tool call (e.g., Edit, Write, Read)
+    pub fn file_path(&self) -> Option<&str> {
+        input_str(&self.tool_input, "file_path")
+    }
+
+    /// Get the `new_string` input of an Edit tool call
+    pub fn new_string(&self) -> Option<&str> {
+        input_str(&self.tool_input, "new_string")
+    }
+
+    /// Get the edits of a MultiEdit tool call
+    ///
+    /// Returns `None` for other tools or if `edits` doesn't have the expected shape.
+    pub fn multi_edits(&self) -> Option<Vec<EditOp>> {
+        multi_edits(&self.tool_name, &self.tool_input)
+    }
+
+    /// Get the typed response of a WebFetch tool call
+    ///
+    /// Returns `None` for other tools or if the response has no content.
+    pub fn web_fetch_response(&self) -> Option<WebFetchResponse> {
+        web_fetch_response(&self.tool_name, &self.tool_response)
+    }
+
+    /// Get the typed response of a WebSearch tool call
+    ///
+    /// Returns `None` for other tools or if the response has no `results` list.
+    pub fn web_search_response(&self) -> Option<WebSearchResponse> {
+        web_search_response(&self.tool_name, &self.tool_response)
+    }
 }
 
 impl Input for PostToolUse {}
 
 impl TranscriptReader for PostToolUse {
-    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
-        let content = fs::read_to_string(&self.transcript_path)?;
-        Ok(parse_transcript(&content)?)
+    fn transcript_path(&self) -> &str {
+        &self.transcript_path
+    }
+}
+
+/// Builder for [`PostToolUse`]. Fields that aren't set are left empty.
+#[derive(Debug, Default)]
+pub struct PostToolUseBuilder {
+    session_id: String,
+    transcript_path: String,
+    tool_name: String,
+    tool_input: HashMap<String, Value>,
+    tool_response: HashMap<String, Value>,
+}
+
+impl PostToolUseBuilder {
+    /// Set the session ID
+    pub fn session_id(mut self, session_id: &str) -> Self {
+        self.session_id = session_id.to_string();
+        self
+    }
+
+    /// Set the transcript path
+    pub fn transcript_path(mut self, transcript_path: &str) -> Self {
+        self.transcript_path = transcript_path.to_string();
+        self
+    }
+
+    /// Set the tool name (e.g., "Bash")
+    pub fn tool_name(mut self, tool_name: &str) -> Self {
+        self.tool_name = tool_name.to_string();
+        self
+    }
+
+    /// Add a tool input parameter
+    pub fn tool_input(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.tool_input.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Add a field of the tool response
+    pub fn tool_response(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.tool_response.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Build the PostToolUse
+    pub fn build(self) -> PostToolUse {
+        PostToolUse {
+            session_id: self.session_id,
+            transcript_path: self.transcript_path,
+            hook_event_name: None,
+            tool_name: self.tool_name,
+            tool_input: self.tool_input,
+            tool_response: self.tool_response,
+        }
     }
 }
 
@@ -57,6 +178,7 @@ impl TranscriptReader for PostToolUse {
 ///
 /// Provides feedback to Claude after a tool has already executed.
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PostToolUseOutput {
     /// Whether to provide feedback to Claude.
@@ -82,6 +204,14 @@ pub struct PostToolUseOutput {
     /// Hide output from transcript mode (default: false)
     #[serde(skip_serializing_if = "is_none")]
     pub suppress_output: Option<bool>,
+
+    /// Hook-specific fields read by Claude Code, such as `additionalContext`
+    #[serde(skip_serializing_if = "is_none")]
+    pub hook_specific_output: Option<Value>,
+
+    /// Metadata for logs and tooling, ignored by Claude Code
+    #[serde(skip_serializing_if = "is_none")]
+    pub metadata: Option<HookMetadata>,
 }
 
 impl PostToolUseOutput {
@@ -105,6 +235,18 @@ impl PostToolUseOutput {
         Self::default()
     }
 
+    /// Add to the reason, on a new line if there is one already
+    ///
+    /// Useful for accumulating feedback from several checks. The reason is only
+    /// shown to Claude with a `block` decision.
+    pub fn append_reason(mut self, extra: &str) -> Self {
+        self.reason = Some(match self.reason.take() {
+            Some(reason) => format!("{reason}\n{extra}"),
+            None => extra.to_string(),
+        });
+        self
+    }
+
     /// Set the continue field to false and provide a stop reason
     pub fn and_stop(mut self, reason: &str) -> Self {
         self.continue_ = Some(false);
@@ -112,11 +254,52 @@ impl PostToolUseOutput {
         self
     }
 
+    /// Stop Claude from continuing after this hook, showing `reason` to the
+    /// user. An alias for [`and_stop`](Self::and_stop).
+    pub fn and_block_processing(self, reason: &str) -> Self {
+        self.and_stop(reason)
+    }
+
     /// Set whether to suppress output in transcript mode
     pub fn and_suppress_output(mut self, suppress: bool) -> Self {
         self.suppress_output = Some(suppress);
         self
     }
+
+    /// Add context for Claude to consider alongside the tool result
+    ///
+    /// Sets `hookSpecificOutput.additionalContext`, keeping any other
+    /// hook-specific fields already present.
+    pub fn with_additional_context(mut self, ctx: &str) -> Self {
+        let mut specific = match self.hook_specific_output.take() {
+            Some(Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        specific.insert(
+            "hookEventName".to_string(),
+            Value::String("PostToolUse".to_string()),
+        );
+        specific.insert(
+            "additionalContext".to_string(),
+            Value::String(ctx.to_string()),
+        );
+        self.hook_specific_output = Some(Value::Object(specific));
+        self
+    }
+
+    /// Set an arbitrary `hookSpecificOutput` payload, replacing any existing one
+    pub fn with_hook_specific_output(mut self, output: Value) -> Self {
+        self.hook_specific_output = Some(output);
+        self
+    }
+
+    /// Annotate the response with the id of the rule that produced it
+    pub fn with_rule_id(mut self, rule_id: &str) -> Self {
+        self.metadata = Some(HookMetadata {
+            rule_id: Some(rule_id.to_string()),
+        });
+        self
+    }
 }
 
 impl HookResponse for PostToolUseOutput {}
@@ -137,6 +320,7 @@ mod tests {
         let post_tool_use = PostToolUse {
             session_id: "test-session".to_string(),
             transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
             tool_name: "Bash".to_string(),
             tool_input: HashMap::new(),
             tool_response,
@@ -149,4 +333,179 @@ mod tests {
         assert_eq!(deserialized.decision, block_output.decision);
         assert_eq!(deserialized.reason, block_output.reason);
     }
+
+    #[test]
+    fn test_post_tool_use_accessors() {
+        let post_tool_use = PostToolUse {
+            session_id: "test-session".to_string(),
+            transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
+            tool_name: "Write".to_string(),
+            tool_input: serde_json::from_value(serde_json::json!({
+                "file_path": "/tmp/notes.md",
+                "content": "hello"
+            }))
+            .unwrap(),
+            tool_response: HashMap::new(),
+        };
+
+        assert_eq!(post_tool_use.file_path(), Some("/tmp/notes.md"));
+        assert_eq!(post_tool_use.bash_command(), None);
+        assert_eq!(post_tool_use.new_string(), None);
+    }
+
+    #[test]
+    fn test_hook_specific_output() {
+        // Omitted entirely when unset
+        let json = serde_json::to_value(PostToolUseOutput::passthrough()).unwrap();
+        assert_eq!(json, serde_json::json!({}));
+
+        let output = PostToolUseOutput::passthrough()
+            .with_hook_specific_output(serde_json::json!({"lintErrors": 2}))
+            .with_additional_context("2 lint errors in src/lib.rs");
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "hookSpecificOutput": {
+                    "lintErrors": 2,
+                    "hookEventName": "PostToolUse",
+                    "additionalContext": "2 lint errors in src/lib.rs"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_append_reason() {
+        let output = PostToolUseOutput::passthrough().append_reason("Missing docs");
+        assert_eq!(output.reason.as_deref(), Some("Missing docs"));
+
+        let output = PostToolUseOutput::block("Formatting is off").append_reason("Unused import");
+        assert_eq!(
+            output.reason.as_deref(),
+            Some("Formatting is off\nUnused import")
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let input = PostToolUse::builder()
+            .session_id("test-session")
+            .transcript_path("/path/to/transcript")
+            .tool_name("Write")
+            .tool_input("file_path", "/src/lib.rs")
+            .tool_response("success", true)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&input).unwrap(),
+            serde_json::json!({
+                "session_id": "test-session",
+                "transcript_path": "/path/to/transcript",
+                "tool_name": "Write",
+                "tool_input": {"file_path": "/src/lib.rs"},
+                "tool_response": {"success": true}
+            })
+        );
+    }
+
+    #[test]
+    fn test_require_tool() {
+        let input = PostToolUse::builder().tool_name("Edit").build();
+        assert!(input.require_tool("Edit").is_some());
+        assert!(input.require_tool("Write").is_none());
+        assert!(input.require_tool("edit").is_none());
+    }
+
+    #[test]
+    fn test_fluent_output() {
+        let output = PostToolUseOutput::block("Not allowed")
+            .and_block_processing("Halted by policy")
+            .and_suppress_output(true)
+            .with_rule_id("policy-1");
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({
+                "decision": "block",
+                "reason": "Not allowed",
+                "continue": false,
+                "stopReason": "Halted by policy",
+                "suppressOutput": true,
+                "metadata": {"ruleId": "policy-1"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_web_fetch_response() {
+        let input = PostToolUse::builder()
+            .tool_name("WebFetch")
+            .tool_input("url", "https://example.com")
+            .tool_response("bytes", 1256)
+            .tool_response("code", 200)
+            .tool_response("codeText", "OK")
+            .tool_response("result", "Example Domain is for documentation.")
+            .tool_response("durationMs", 412)
+            .tool_response("url", "https://example.com/")
+            .build();
+        assert_eq!(
+            input.web_fetch_response(),
+            Some(WebFetchResponse {
+                url: Some("https://example.com/".to_string()),
+                content: "Example Domain is for documentation.".to_string(),
+                code: Some(200),
+            })
+        );
+
+        let empty = PostToolUse::builder().tool_name("WebFetch").build();
+        assert_eq!(empty.web_fetch_response(), None);
+        let bash = PostToolUse::builder()
+            .tool_name("Bash")
+            .tool_response("result", "ok")
+            .build();
+        assert_eq!(bash.web_fetch_response(), None);
+    }
+
+    #[test]
+    fn test_web_search_response() {
+        let input = PostToolUse::builder()
+            .tool_name("WebSearch")
+            .tool_input("query", "rust hooks")
+            .tool_response("query", "rust hooks")
+            .tool_response(
+                "results",
+                serde_json::json!([
+                    {
+                        "tool_use_id": "srvtoolu_1",
+                        "content": [
+                            {"title": "Hooks reference", "url": "https://docs.example.com/hooks"},
+                            {"title": "code-hooks", "url": "https://crates.io/crates/code-hooks"}
+                        ]
+                    },
+                    "Here are the top results for rust hooks.",
+                    {"title": "Rust", "url": "https://www.rust-lang.org"},
+                    {"snippet": "no title or url"}
+                ]),
+            )
+            .tool_response("durationSeconds", 1.5)
+            .build();
+        let response = input.web_search_response().unwrap();
+        assert_eq!(response.query.as_deref(), Some("rust hooks"));
+        let urls: Vec<&str> = response.results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://docs.example.com/hooks",
+                "https://crates.io/crates/code-hooks",
+                "https://www.rust-lang.org"
+            ]
+        );
+        assert_eq!(response.results[0].title, "Hooks reference");
+
+        let unexpected = PostToolUse::builder()
+            .tool_name("WebSearch")
+            .tool_response("results", "not a list")
+            .build();
+        assert_eq!(unexpected.web_search_response(), None);
+    }
 }
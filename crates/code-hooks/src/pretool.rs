@@ -1,31 +1,101 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::fs;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
-use crate::Result;
-use crate::io::{Decision, HookResponse, Input, TranscriptReader, is_none};
-use claude_transcript::TranscriptEntry;
-use claude_transcript::parse::parse_transcript;
+use crate::ToolInput;
+use crate::io::{
+    Decision, HookMetadata, HookResponse, Input, TranscriptReader, input_str, is_none,
+};
+use crate::tools::{
+    EditOp, ToolName, WebFetchInput, WebSearchInput, is_read_only, multi_edits, typed_input,
+};
 
 /// Input structure for PreToolUse hooks.
 ///
 /// PreToolUse hooks run after Claude creates tool parameters but before
 /// processing the tool call. They can approve or block the operation.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct PreToolUse {
     /// Unique identifier for the current Claude Code session
     pub session_id: String,
     /// Path to the conversation transcript JSON file
     pub transcript_path: String,
+    /// Name of the event, e.g. "PreToolUse". Older Claude Code versions don't send it.
+    #[serde(default, skip_serializing_if = "is_none", alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
     /// Name of the tool being called (e.g., "Bash", "Write", "Edit")
     pub tool_name: String,
     /// Tool-specific input parameters. The exact schema depends on the tool.
     pub tool_input: HashMap<String, Value>,
+    /// The session's permission mode, e.g. "plan". Older Claude Code versions
+    /// don't send it; see [`PreToolUse::permission_mode`] for the parsed form.
+    #[serde(default, skip_serializing_if = "is_none")]
+    pub permission_mode: Option<String>,
+}
+
+/// How Claude Code is handling permission prompts in the session
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PermissionMode {
+    /// Prompt for permission as configured
+    Default,
+    /// Accept file edits without prompting
+    AcceptEdits,
+    /// Skip all permission prompts
+    BypassPermissions,
+    /// Plan mode: Claude explores and plans without making changes
+    Plan,
+    /// A mode this crate doesn't know about
+    Other(String),
+}
+
+impl PermissionMode {
+    /// The mode as Claude Code names it in payloads
+    pub fn as_str(&self) -> &str {
+        match self {
+            PermissionMode::Default => "default",
+            PermissionMode::AcceptEdits => "acceptEdits",
+            PermissionMode::BypassPermissions => "bypassPermissions",
+            PermissionMode::Plan => "plan",
+            PermissionMode::Other(other) => other,
+        }
+    }
+}
+
+impl FromStr for PermissionMode {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "default" => PermissionMode::Default,
+            "acceptEdits" => PermissionMode::AcceptEdits,
+            "bypassPermissions" => PermissionMode::BypassPermissions,
+            "plan" => PermissionMode::Plan,
+            other => PermissionMode::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for PermissionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl PreToolUse {
+    /// Start building a PreToolUse, e.g. to test a hook without writing JSON
+    pub fn builder() -> PreToolUseBuilder {
+        PreToolUseBuilder::default()
+    }
+
     /// Create an approval response that bypasses the permission system
     ///
     /// The tool executes immediately. The reason is shown to the user but not Claude.
@@ -46,14 +116,373 @@ impl PreToolUse {
     pub fn passthrough(&self) -> PreToolUseOutput {
         PreToolUseOutput::passthrough()
     }
+
+    /// The tool being called, for matching without comparing strings
+    pub fn tool(&self) -> ToolName {
+        let Ok(tool) = self.tool_name.parse();
+        tool
+    }
+
+    /// The session's permission mode, if Claude Code sent one
+    pub fn permission_mode(&self) -> Option<PermissionMode> {
+        let Ok(mode) = self.permission_mode.as_deref()?.parse();
+        Some(mode)
+    }
+
+    /// Get this input only if it's a call to the tool `name`, so other tools
+    /// can be passed through early:
+    ///
+    /// ```rust,no_run
+    /// use code_hooks::{HookResponse, Input, PreToolUse, PreToolUseOutput};
+    ///
+    /// let input = PreToolUse::read().unwrap();
+    /// let Some(input) = input.require_tool("Edit") else {
+    ///     PreToolUseOutput::passthrough().respond()
+    /// };
+    /// ```
+    pub fn require_tool(&self, name: &str) -> Option<&Self> {
+        (self.tool_name == name).then_some(self)
+    }
+
+    /// Get the `command` input of a Bash tool call
+    pub fn bash_command(&self) -> Option<&str> {
+        input_str(&self.tool_input, "command")
+    }
+
+    /// Get the `file_path` input of a file tool call (e.g., Edit, Write, Read)
+    pub fn file_path(&self) -> Option<&str> {
+        input_str(&self.tool_input, "file_path")
+    }
+
+    /// Get the `new_string` input of an Edit tool call
+    pub fn new_string(&self) -> Option<&str> {
+        input_str(&self.tool_input, "new_string")
+    }
+
+    /// Get the edits of a MultiEdit tool call
+    ///
+    /// Returns `None` for other tools or if `edits` doesn't have the expected shape.
+    pub fn multi_edits(&self) -> Option<Vec<EditOp>> {
+        multi_edits(&self.tool_name, &self.tool_input)
+    }
+
+    /// Whether this tool call only reads state
+    ///
+    /// True for read-only tools (`Read`, `Grep`, `Glob`, `LS`, `NotebookRead`)
+    /// and for Bash commands made up entirely of allowlisted inspection commands
+    /// such as `cat`, `ls` or `git status`. The Bash check is a conservative
+    /// heuristic: it doesn't interpret quoting, redirection or substitution, and
+    /// rejects any command using them.
+    pub fn is_read_only(&self) -> bool {
+        is_read_only(&self.tool_name, &self.tool_input)
+    }
+
+    /// Get the typed input of a WebFetch tool call
+    ///
+    /// Returns `None` for other tools or if `url` or `prompt` is missing.
+    pub fn as_web_fetch(&self) -> Option<WebFetchInput> {
+        typed_input(&self.tool_name, "WebFetch", &self.tool_input)
+    }
+
+    /// Get the typed input of a WebSearch tool call
+    ///
+    /// Returns `None` for other tools or if `query` is missing.
+    pub fn as_web_search(&self) -> Option<WebSearchInput> {
+        typed_input(&self.tool_name, "WebSearch", &self.tool_input)
+    }
+
+    /// Parse the tool input into a typed [`ToolInput`] based on the tool name
+    ///
+    /// Falls back to `ToolInput::Other` for unrecognized tools or mismatched shapes.
+    pub fn parsed_tool_input(&self) -> ToolInput {
+        ToolInput::parse(&self.tool_name, &self.tool_input)
+    }
+
+    /// Create an allow response using the `hookSpecificOutput` protocol
+    ///
+    /// The tool executes immediately. The reason is shown to the user but not Claude.
+    pub fn allow(&self, reason: &str) -> PreToolUseOutput {
+        PreToolUseOutput::allow(reason)
+    }
+
+    /// Create a deny response using the `hookSpecificOutput` protocol
+    ///
+    /// The reason is passed to Claude as feedback.
+    pub fn deny(&self, reason: &str) -> PreToolUseOutput {
+        PreToolUseOutput::deny(reason)
+    }
+
+    /// Create an ask response using the `hookSpecificOutput` protocol
+    ///
+    /// The user is asked to confirm the tool call. The reason is shown to the user.
+    pub fn ask(&self, reason: &str) -> PreToolUseOutput {
+        PreToolUseOutput::ask(reason)
+    }
+
+    /// Ask the user the first time `key` is seen in this session, and fall
+    /// back to the normal permission flow on later calls
+    ///
+    /// Each hook call is a new process, so seen keys are recorded in a
+    /// per-session file only the current user can read, under
+    /// `$XDG_RUNTIME_DIR/code-hooks` or else `~/.cache/code-hooks`. Hooks can't
+    /// see how the user answered, so later calls pass through rather than
+    /// allow, leaving the decision to Claude Code's permission settings. If the
+    /// record can't be read or written, this always asks.
+    pub fn ask_once(&self, key: &str, reason: &str) -> PreToolUseOutput {
+        match ask_once_dir() {
+            Some(dir) => self.ask_once_in(&dir, key, reason),
+            None => PreToolUseOutput::ask(reason),
+        }
+    }
+
+    /// [`ask_once`](Self::ask_once), keeping the records in `dir`
+    fn ask_once_in(&self, dir: &Path, key: &str, reason: &str) -> PreToolUseOutput {
+        match record_key(&ask_once_path(dir, &self.session_id), key) {
+            Ok(false) => PreToolUseOutput::passthrough(),
+            Ok(true) | Err(_) => PreToolUseOutput::ask(reason),
+        }
+    }
+}
+
+/// How long `ask_once` records are kept after their session was last active
+const ASK_ONCE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Per-user directory holding the `ask_once` records, if one can be found
+fn ask_once_dir() -> Option<PathBuf> {
+    let non_empty = |var| std::env::var_os(var).filter(|value| !value.is_empty());
+    non_empty("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".cache")))
+        .map(|dir| dir.join("code-hooks"))
+}
+
+/// File in `dir` recording the `ask_once` keys seen in a session
+fn ask_once_path(dir: &Path, session_id: &str) -> PathBuf {
+    let session: String = session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("ask-once-{session}.jsonl"))
+}
+
+/// Record `key` in the file at `path`, returning true if it wasn't already there.
+///
+/// The file and its directory are created readable by the current user only,
+/// and starting a new record removes those of sessions that have gone stale.
+fn record_key(path: &Path, key: &str) -> io::Result<bool> {
+    let line = serde_json::to_string(key)?;
+    match fs::read_to_string(path) {
+        Ok(content) if content.lines().any(|seen| seen == line) => return Ok(false),
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if let Some(dir) = path.parent() {
+                create_private_dir(dir)?;
+                remove_stale_records(dir);
+            }
+        }
+        Err(e) => return Err(e),
+    }
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(true)
+}
+
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Best-effort removal of `ask_once` records older than [`ASK_ONCE_MAX_AGE`]
+fn remove_stale_records(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_record = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("ask-once-") && name.ends_with(".jsonl"));
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > ASK_ONCE_MAX_AGE);
+        if is_record && stale {
+            let _ = fs::remove_file(path);
+        }
+    }
 }
 
 impl Input for PreToolUse {}
 
 impl TranscriptReader for PreToolUse {
-    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
-        let content = fs::read_to_string(&self.transcript_path)?;
-        Ok(parse_transcript(&content)?)
+    fn transcript_path(&self) -> &str {
+        &self.transcript_path
+    }
+}
+
+/// Builder for [`PreToolUse`]. Fields that aren't set are left empty.
+#[derive(Debug, Default)]
+pub struct PreToolUseBuilder {
+    session_id: String,
+    transcript_path: String,
+    tool_name: String,
+    tool_input: HashMap<String, Value>,
+    permission_mode: Option<String>,
+}
+
+impl PreToolUseBuilder {
+    /// Set the session ID
+    pub fn session_id(mut self, session_id: &str) -> Self {
+        self.session_id = session_id.to_string();
+        self
+    }
+
+    /// Set the transcript path
+    pub fn transcript_path(mut self, transcript_path: &str) -> Self {
+        self.transcript_path = transcript_path.to_string();
+        self
+    }
+
+    /// Set the tool name (e.g., "Bash")
+    pub fn tool_name(mut self, tool_name: &str) -> Self {
+        self.tool_name = tool_name.to_string();
+        self
+    }
+
+    /// Add a tool input parameter
+    pub fn tool_input(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.tool_input.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Set the permission mode (e.g., "plan")
+    pub fn permission_mode(mut self, permission_mode: &str) -> Self {
+        self.permission_mode = Some(permission_mode.to_string());
+        self
+    }
+
+    /// Build the PreToolUse
+    pub fn build(self) -> PreToolUse {
+        PreToolUse {
+            session_id: self.session_id,
+            transcript_path: self.transcript_path,
+            hook_event_name: None,
+            tool_name: self.tool_name,
+            tool_input: self.tool_input,
+            permission_mode: self.permission_mode,
+        }
+    }
+}
+
+/// A PreToolUse check: `Some` response to decide the tool call, `None` to
+/// leave it to the next check
+type Check = Box<dyn Fn(&PreToolUse) -> Option<PreToolUseOutput>>;
+
+/// Runs PreToolUse checks in order and responds with the first one that
+/// returns a response, or a passthrough if none do.
+///
+/// ```rust,no_run
+/// use code_hooks::{HookResponse, Input, PreToolUse, PreToolUseChecker, PreToolUseOutput};
+///
+/// let checker = PreToolUseChecker::new()
+///     .add(|input| {
+///         input
+///             .bash_command()
+///             .filter(|command| command.contains("rm -rf"))
+///             .map(|_| PreToolUseOutput::block("Dangerous command"))
+///     })
+///     .add(|input| input.is_read_only().then(|| PreToolUseOutput::approve("Read-only")));
+///
+/// checker.run(&PreToolUse::read().unwrap()).respond();
+/// ```
+#[derive(Default)]
+pub struct PreToolUseChecker {
+    checks: Vec<Check>,
+}
+
+impl PreToolUseChecker {
+    /// Create a checker with no checks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a check, run after those already added
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(
+        mut self,
+        check: impl Fn(&PreToolUse) -> Option<PreToolUseOutput> + 'static,
+    ) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Run the checks in order, stopping at the first that returns a response
+    pub fn run(&self, input: &PreToolUse) -> PreToolUseOutput {
+        self.checks
+            .iter()
+            .find_map(|check| check(input))
+            .unwrap_or_else(PreToolUseOutput::passthrough)
+    }
+}
+
+/// Permission decision used by the `hookSpecificOutput` PreToolUse protocol.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionDecision {
+    /// Bypass the permission system and run the tool
+    Allow,
+    /// Prevent the tool call and provide feedback to Claude
+    Deny,
+    /// Ask the user to confirm the tool call
+    Ask,
+}
+
+/// Hook-specific output for PreToolUse hooks.
+///
+/// Newer Claude Code versions read the permission decision from this nested
+/// object, superseding the top-level `decision` field.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PreToolUseHookSpecificOutput {
+    /// Name of the hook event, always "PreToolUse"
+    pub hook_event_name: String,
+
+    /// Whether to allow, deny, or ask about the tool call
+    #[serde(skip_serializing_if = "is_none")]
+    pub permission_decision: Option<PermissionDecision>,
+
+    /// Explanation for the permission decision. Usage depends on decision type:
+    /// - For `Allow` and `Ask`: Shown to user but not Claude
+    /// - For `Deny`: Shown to Claude as feedback
+    #[serde(skip_serializing_if = "is_none")]
+    pub permission_decision_reason: Option<String>,
+}
+
+impl PreToolUseHookSpecificOutput {
+    fn new(decision: PermissionDecision, reason: &str) -> Self {
+        Self {
+            hook_event_name: "PreToolUse".to_string(),
+            permission_decision: Some(decision),
+            permission_decision_reason: Some(reason.to_string()),
+        }
     }
 }
 
@@ -61,6 +490,7 @@ impl TranscriptReader for PreToolUse {
 ///
 /// Controls whether a tool call proceeds and provides feedback to Claude.
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PreToolUseOutput {
     /// Whether to approve or block the tool call.
@@ -90,6 +520,15 @@ pub struct PreToolUseOutput {
     /// Hide output from transcript mode (default: false)
     #[serde(skip_serializing_if = "is_none")]
     pub suppress_output: Option<bool>,
+
+    /// Metadata for logs and tooling, ignored by Claude Code
+    #[serde(skip_serializing_if = "is_none")]
+    pub metadata: Option<HookMetadata>,
+
+    /// Permission decision in the newer protocol. When present, Claude Code
+    /// versions that understand it ignore the legacy `decision` field.
+    #[serde(skip_serializing_if = "is_none")]
+    pub hook_specific_output: Option<PreToolUseHookSpecificOutput>,
 }
 
 impl PreToolUseOutput {
@@ -123,6 +562,34 @@ impl PreToolUseOutput {
         Self::default()
     }
 
+    /// Create an allow response using the `hookSpecificOutput` protocol
+    ///
+    /// The tool executes immediately. The reason is shown to the user but not Claude.
+    pub fn allow(reason: &str) -> Self {
+        Self::with_permission(PermissionDecision::Allow, reason)
+    }
+
+    /// Create a deny response using the `hookSpecificOutput` protocol
+    ///
+    /// The reason is passed to Claude as feedback.
+    pub fn deny(reason: &str) -> Self {
+        Self::with_permission(PermissionDecision::Deny, reason)
+    }
+
+    /// Create an ask response using the `hookSpecificOutput` protocol
+    ///
+    /// The user is asked to confirm the tool call. The reason is shown to the user.
+    pub fn ask(reason: &str) -> Self {
+        Self::with_permission(PermissionDecision::Ask, reason)
+    }
+
+    fn with_permission(decision: PermissionDecision, reason: &str) -> Self {
+        Self {
+            hook_specific_output: Some(PreToolUseHookSpecificOutput::new(decision, reason)),
+            ..Default::default()
+        }
+    }
+
     /// Set the continue field to false and provide a stop reason
     pub fn and_stop(mut self, reason: &str) -> Self {
         self.continue_ = Some(false);
@@ -130,11 +597,25 @@ impl PreToolUseOutput {
         self
     }
 
+    /// Stop Claude from continuing after this hook, showing `reason` to the
+    /// user. An alias for [`and_stop`](Self::and_stop).
+    pub fn and_block_processing(self, reason: &str) -> Self {
+        self.and_stop(reason)
+    }
+
     /// Set whether to suppress output in transcript mode
     pub fn and_suppress_output(mut self, suppress: bool) -> Self {
         self.suppress_output = Some(suppress);
         self
     }
+
+    /// Annotate the response with the id of the rule that produced it
+    pub fn with_rule_id(mut self, rule_id: &str) -> Self {
+        self.metadata = Some(HookMetadata {
+            rule_id: Some(rule_id.to_string()),
+        });
+        self
+    }
 }
 
 impl HookResponse for PreToolUseOutput {}
@@ -142,6 +623,7 @@ impl HookResponse for PreToolUseOutput {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use claude_transcript::TranscriptEntry;
 
     #[test]
     fn test_pre_tool_use_roundtrip() {
@@ -149,8 +631,10 @@ mod tests {
         let pre_tool_use = PreToolUse {
             session_id: "test-session".to_string(),
             transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
             tool_name: "Bash".to_string(),
             tool_input: HashMap::new(),
+            permission_mode: None,
         };
 
         // Test approve response
@@ -160,4 +644,434 @@ mod tests {
         assert_eq!(deserialized.decision, approve_output.decision);
         assert_eq!(deserialized.reason, approve_output.reason);
     }
+
+    #[test]
+    fn test_pre_tool_use_permission_decision() {
+        let deny = serde_json::to_value(PreToolUseOutput::deny("Not allowed")).unwrap();
+        assert_eq!(
+            deny,
+            serde_json::json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "PreToolUse",
+                    "permissionDecision": "deny",
+                    "permissionDecisionReason": "Not allowed"
+                }
+            })
+        );
+
+        let ask = serde_json::to_value(PreToolUseOutput::ask("Confirm please")).unwrap();
+        assert_eq!(ask["hookSpecificOutput"]["hookEventName"], "PreToolUse");
+        assert_eq!(ask["hookSpecificOutput"]["permissionDecision"], "ask");
+
+        // Legacy responses don't emit the nested object
+        let approve = serde_json::to_value(PreToolUseOutput::approve("ok")).unwrap();
+        assert_eq!(
+            approve,
+            serde_json::json!({"decision": "approve", "reason": "ok"})
+        );
+    }
+
+    #[test]
+    fn test_pre_tool_use_read_from() {
+        let json = r#"{
+            "session_id": "test-session",
+            "transcript_path": "/path/to/transcript",
+            "tool_name": "Bash",
+            "tool_input": {"command": "ls -la"}
+        }"#;
+
+        let input = PreToolUse::read_from(std::io::Cursor::new(json.as_bytes())).unwrap();
+        assert_eq!(input.session_id, "test-session");
+        assert_eq!(input.tool_name, "Bash");
+        assert_eq!(input.tool_input["command"], "ls -la");
+    }
+
+    #[test]
+    fn test_pre_tool_use_accessors() {
+        let bash = pre_tool_use("Bash", serde_json::json!({"command": "ls -la"}));
+        assert_eq!(bash.bash_command(), Some("ls -la"));
+        assert_eq!(bash.file_path(), None);
+
+        let edit = pre_tool_use(
+            "Edit",
+            serde_json::json!({
+                "file_path": "/tmp/main.rs",
+                "old_string": "foo",
+                "new_string": "bar"
+            }),
+        );
+        assert_eq!(edit.file_path(), Some("/tmp/main.rs"));
+        assert_eq!(edit.new_string(), Some("bar"));
+        assert_eq!(edit.bash_command(), None);
+
+        let other = pre_tool_use("Task", serde_json::json!({"command": 42, "prompt": "hi"}));
+        assert_eq!(other.bash_command(), None);
+        assert_eq!(other.file_path(), None);
+        assert_eq!(other.new_string(), None);
+    }
+
+    #[test]
+    fn test_pre_tool_use_parsed_tool_input() {
+        let bash = pre_tool_use(
+            "Bash",
+            serde_json::json!({"command": "cargo test", "description": "Run tests"}),
+        );
+        assert_eq!(
+            bash.parsed_tool_input(),
+            ToolInput::Bash {
+                command: "cargo test".to_string(),
+                description: Some("Run tests".to_string())
+            }
+        );
+
+        let other = pre_tool_use("Task", serde_json::json!({"prompt": "hi"}));
+        assert!(matches!(other.parsed_tool_input(), ToolInput::Other(_)));
+    }
+
+    #[test]
+    fn test_pre_tool_use_rule_id() {
+        let output = PreToolUseOutput::block("No force pushes").with_rule_id("git-001");
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["metadata"]["ruleId"], "git-001");
+        assert_eq!(json["decision"], "block");
+
+        // Omitted when unset
+        let json = serde_json::to_value(PreToolUseOutput::block("No")).unwrap();
+        assert!(json.get("metadata").is_none());
+    }
+
+    #[test]
+    fn test_pre_tool_use_read_transcript() {
+        let transcript = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            transcript.path(),
+            concat!(
+                r#"{"type":"user","message":{"role":"user","content":"hello"},"uuid":"u1","timestamp":"2025-01-01T00:00:00Z","cwd":"/test","sessionId":"test-session","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}"#,
+                "\n",
+                r#"{"type":"summary","summary":"Test summary","leafUuid":"u1"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let mut input = pre_tool_use("Bash", serde_json::json!({"command": "ls"}));
+        input.transcript_path = transcript.path().to_string_lossy().to_string();
+
+        let entries = input.read_transcript().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(entries[0], TranscriptEntry::User(_)));
+        assert!(matches!(entries[1], TranscriptEntry::Summary(_)));
+    }
+
+    #[test]
+    fn test_pre_tool_use_web_fetch() {
+        let input = pre_tool_use(
+            "WebFetch",
+            serde_json::json!({"url": "http://10.0.0.1/admin", "prompt": "Summarize"}),
+        );
+        assert_eq!(
+            input.as_web_fetch(),
+            Some(WebFetchInput {
+                url: "http://10.0.0.1/admin".to_string(),
+                prompt: "Summarize".to_string(),
+            })
+        );
+        assert_eq!(input.as_web_search(), None);
+    }
+
+    #[test]
+    fn test_pre_tool_use_web_search() {
+        let input = pre_tool_use(
+            "WebSearch",
+            serde_json::json!({"query": "claude code hooks", "allowed_domains": ["docs.anthropic.com"]}),
+        );
+        let search = input.as_web_search().unwrap();
+        assert_eq!(search.query, "claude code hooks");
+        assert_eq!(
+            search.allowed_domains,
+            Some(vec!["docs.anthropic.com".to_string()])
+        );
+        assert_eq!(search.blocked_domains, None);
+        assert_eq!(input.as_web_fetch(), None);
+    }
+
+    #[test]
+    fn test_pre_tool_use_is_read_only() {
+        let read = pre_tool_use("Read", serde_json::json!({"file_path": "/tmp/a"}));
+        assert!(read.is_read_only());
+
+        let write = pre_tool_use(
+            "Write",
+            serde_json::json!({"file_path": "/tmp/a", "content": "x"}),
+        );
+        assert!(!write.is_read_only());
+
+        let status = pre_tool_use("Bash", serde_json::json!({"command": "git status"}));
+        assert!(status.is_read_only());
+    }
+
+    #[test]
+    fn test_pre_tool_use_read_from_trailing_bytes() {
+        let json = "{\"session_id\":\"s\",\"transcript_path\":\"/t\",\"tool_name\":\"Bash\",\"tool_input\":{}}";
+
+        let padded = format!("{json}\n\n  \r\n\t");
+        let input = PreToolUse::read_from(std::io::Cursor::new(padded.as_bytes())).unwrap();
+        assert_eq!(input.tool_name, "Bash");
+
+        let mut invalid = json.as_bytes().to_vec();
+        invalid.extend_from_slice(b"\n\xff\xfe");
+        let err = PreToolUse::read_from(std::io::Cursor::new(invalid)).unwrap_err();
+        assert!(matches!(err, crate::Error::InputParse { .. }));
+    }
+
+    #[test]
+    fn test_pre_tool_use_ask_once() {
+        let input = PreToolUse {
+            session_id: "ask-once-test".to_string(),
+            transcript_path: "/tmp/transcript.json".to_string(),
+            hook_event_name: None,
+            tool_name: "Bash".to_string(),
+            tool_input: HashMap::new(),
+            permission_mode: None,
+        };
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("code-hooks");
+        let path = ask_once_path(&dir, &input.session_id);
+
+        // Records of stale sessions are removed when a new one starts
+        create_private_dir(&dir).unwrap();
+        let stale = ask_once_path(&dir, "stale");
+        fs::write(&stale, "\"key\"\n").unwrap();
+        let week_ago = std::time::SystemTime::now() - ASK_ONCE_MAX_AGE - Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(week_ago)
+            .unwrap();
+        let recent = ask_once_path(&dir, "recent");
+        fs::write(&recent, "\"key\"\n").unwrap();
+
+        let decision = |output: PreToolUseOutput| {
+            output
+                .hook_specific_output
+                .and_then(|specific| specific.permission_decision)
+        };
+        assert_eq!(
+            decision(input.ask_once_in(&dir, "force-push", "Force push?")),
+            Some(PermissionDecision::Ask)
+        );
+        assert!(!stale.exists());
+        assert!(recent.exists());
+
+        // Later calls leave the decision to the permission system
+        let again = input.ask_once_in(&dir, "force-push", "Force push?");
+        assert_eq!(
+            serde_json::to_value(&again).unwrap(),
+            serde_json::to_value(PreToolUseOutput::passthrough()).unwrap()
+        );
+        assert_eq!(
+            decision(input.ask_once_in(&dir, "rm-rf", "Delete?")),
+            Some(PermissionDecision::Ask)
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_pre_tool_use_multi_edits() {
+        let json = r#"{
+            "session_id": "s",
+            "transcript_path": "/t",
+            "tool_name": "MultiEdit",
+            "tool_input": {
+                "file_path": "/src/lib.rs",
+                "edits": [
+                    {"old_string": "foo", "new_string": "bar"},
+                    {"old_string": "a", "new_string": "b", "replace_all": true}
+                ]
+            }
+        }"#;
+        let mut input: PreToolUse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            input.multi_edits(),
+            Some(vec![
+                EditOp {
+                    old_string: "foo".to_string(),
+                    new_string: "bar".to_string(),
+                    replace_all: None,
+                },
+                EditOp {
+                    old_string: "a".to_string(),
+                    new_string: "b".to_string(),
+                    replace_all: Some(true),
+                },
+            ])
+        );
+
+        input.tool_input.insert(
+            "edits".to_string(),
+            serde_json::json!([{"old_string": "x"}]),
+        );
+        assert_eq!(input.multi_edits(), None);
+
+        input.tool_name = "Edit".to_string();
+        assert_eq!(input.multi_edits(), None);
+    }
+
+    #[test]
+    fn test_pre_tool_use_tool() {
+        let mut input = PreToolUse {
+            session_id: "s".to_string(),
+            transcript_path: "/t".to_string(),
+            hook_event_name: None,
+            tool_name: "Edit".to_string(),
+            tool_input: HashMap::new(),
+            permission_mode: None,
+        };
+        assert_eq!(input.tool(), ToolName::Edit);
+
+        input.tool_name = "mcp__fs__read".to_string();
+        assert_eq!(input.tool(), ToolName::Other("mcp__fs__read".to_string()));
+    }
+
+    #[test]
+    fn test_builder() {
+        let input = PreToolUse::builder()
+            .session_id("test-session")
+            .transcript_path("/path/to/transcript")
+            .tool_name("Bash")
+            .tool_input("command", "cargo test")
+            .tool_input("timeout", 60000)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&input).unwrap(),
+            serde_json::json!({
+                "session_id": "test-session",
+                "transcript_path": "/path/to/transcript",
+                "tool_name": "Bash",
+                "tool_input": {"command": "cargo test", "timeout": 60000}
+            })
+        );
+        assert_eq!(input.bash_command(), Some("cargo test"));
+    }
+
+    fn pre_tool_use(tool_name: &str, tool_input: Value) -> PreToolUse {
+        PreToolUse {
+            session_id: "test-session".to_string(),
+            transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
+            tool_name: tool_name.to_string(),
+            tool_input: serde_json::from_value(tool_input).unwrap(),
+            permission_mode: None,
+        }
+    }
+
+    #[test]
+    fn test_hook_event_name() {
+        let with_name: PreToolUse = serde_json::from_str(
+            r#"{"session_id":"s","transcript_path":"/t","hook_event_name":"PreToolUse","tool_name":"Bash","tool_input":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(with_name.hook_event_name.as_deref(), Some("PreToolUse"));
+        let json = serde_json::to_value(&with_name).unwrap();
+        assert_eq!(json["hook_event_name"], "PreToolUse");
+
+        let camel: PreToolUse = serde_json::from_str(
+            r#"{"session_id":"s","transcript_path":"/t","hookEventName":"PreToolUse","tool_name":"Bash","tool_input":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(camel.hook_event_name.as_deref(), Some("PreToolUse"));
+
+        let without: PreToolUse = serde_json::from_str(
+            r#"{"session_id":"s","transcript_path":"/t","tool_name":"Bash","tool_input":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(without.hook_event_name, None);
+        let json = serde_json::to_value(&without).unwrap();
+        assert!(json.get("hook_event_name").is_none());
+    }
+
+    #[test]
+    fn test_require_tool() {
+        let input = PreToolUse::builder().tool_name("Edit").build();
+        assert!(input.require_tool("Edit").is_some());
+        assert!(input.require_tool("Write").is_none());
+        assert!(input.require_tool("edit").is_none());
+    }
+
+    #[test]
+    fn test_checker_first_response_wins() {
+        let checker = PreToolUseChecker::new()
+            .add(|input| {
+                input
+                    .file_path()
+                    .filter(|path| path.ends_with(".env"))
+                    .map(|_| PreToolUseOutput::block("Secrets file"))
+            })
+            .add(|input| {
+                input
+                    .bash_command()
+                    .filter(|command| command.contains("rm -rf"))
+                    .map(|_| PreToolUseOutput::block("Dangerous command"))
+            })
+            .add(|_| Some(PreToolUseOutput::approve("Unreachable")));
+
+        let input = PreToolUse::builder()
+            .tool_name("Bash")
+            .tool_input("command", "rm -rf /")
+            .build();
+        let output = checker.run(&input);
+        assert_eq!(output.decision, Some(Decision::Block));
+        assert_eq!(output.reason.as_deref(), Some("Dangerous command"));
+
+        let output = PreToolUseChecker::new().run(&input);
+        assert_eq!(output.decision, None);
+    }
+
+    #[test]
+    fn test_permission_mode() {
+        let input: PreToolUse = serde_json::from_str(
+            r#"{"session_id":"s","transcript_path":"/t","tool_name":"Bash","tool_input":{},"permission_mode":"plan"}"#,
+        )
+        .unwrap();
+        assert_eq!(input.permission_mode(), Some(PermissionMode::Plan));
+        assert_eq!(
+            serde_json::to_value(&input).unwrap()["permission_mode"],
+            "plan"
+        );
+
+        let input = PreToolUse::builder().permission_mode("acceptEdits").build();
+        assert_eq!(input.permission_mode(), Some(PermissionMode::AcceptEdits));
+        let input = PreToolUse::builder().permission_mode("yolo").build();
+        assert_eq!(
+            input.permission_mode(),
+            Some(PermissionMode::Other("yolo".to_string()))
+        );
+        assert_eq!(PreToolUse::builder().build().permission_mode(), None);
+    }
+
+    #[test]
+    fn test_fluent_output() {
+        let output = PreToolUseOutput::block("Not allowed")
+            .and_block_processing("Halted by policy")
+            .and_suppress_output(true)
+            .with_rule_id("policy-1");
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({
+                "decision": "block",
+                "reason": "Not allowed",
+                "continue": false,
+                "stopReason": "Halted by policy",
+                "suppressOutput": true,
+                "metadata": {"ruleId": "policy-1"}
+            })
+        );
+    }
 }
@@ -0,0 +1,60 @@
+use crate::{
+    EventKind, Notification, NotificationOutput, PostToolUse, PostToolUseOutput, PreToolUse,
+    PreToolUseOutput, Stop, StopOutput, SubagentStop, SubagentStopOutput, UserPromptSubmit,
+    UserPromptSubmitOutput,
+};
+use schemars::schema_for;
+use serde_json::Value;
+
+/// JSON Schema for the input Claude Code sends to an event's hooks.
+///
+/// `event` is a short name or `hook_event_name` as accepted by [`EventKind`];
+/// returns `None` for unknown events.
+pub fn input_schema(event: &str) -> Option<Value> {
+    let schema = match event.parse::<EventKind>().ok()? {
+        EventKind::PreToolUse => schema_for!(PreToolUse),
+        EventKind::PostToolUse => schema_for!(PostToolUse),
+        EventKind::Notification => schema_for!(Notification),
+        EventKind::Stop => schema_for!(Stop),
+        EventKind::SubagentStop => schema_for!(SubagentStop),
+        EventKind::UserPromptSubmit => schema_for!(UserPromptSubmit),
+    };
+    Some(schema.to_value())
+}
+
+/// JSON Schema for the output an event's hooks may respond with.
+///
+/// Accepts the same event names as [`input_schema`].
+pub fn output_schema(event: &str) -> Option<Value> {
+    let schema = match event.parse::<EventKind>().ok()? {
+        EventKind::PreToolUse => schema_for!(PreToolUseOutput),
+        EventKind::PostToolUse => schema_for!(PostToolUseOutput),
+        EventKind::Notification => schema_for!(NotificationOutput),
+        EventKind::Stop => schema_for!(StopOutput),
+        EventKind::SubagentStop => schema_for!(SubagentStopOutput),
+        EventKind::UserPromptSubmit => schema_for!(UserPromptSubmitOutput),
+    };
+    Some(schema.to_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretool_input_schema() {
+        let schema = input_schema("PreToolUse").unwrap();
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&Value::from("tool_name")));
+        assert!(!required.contains(&Value::from("hook_event_name")));
+        assert_eq!(input_schema("pretool"), Some(schema));
+    }
+
+    #[test]
+    fn test_output_schema() {
+        let schema = output_schema("Stop").unwrap();
+        assert!(schema["properties"]["stopReason"].is_object());
+        assert!(schema["properties"].get("continue").is_some());
+        assert_eq!(output_schema("Unknown"), None);
+    }
+}
@@ -1,22 +1,24 @@
+use claude_transcript::analysis::last_assistant_message;
 use serde::{Deserialize, Serialize};
-use std::fs;
 
-use crate::Result;
-use crate::io::{Decision, HookResponse, Input, TranscriptReader, is_none};
-use claude_transcript::TranscriptEntry;
-use claude_transcript::parse::parse_transcript;
+use crate::error::Result;
+use crate::io::{Decision, HookMetadata, HookResponse, Input, TranscriptReader, is_none};
 
 /// Input structure for Stop hooks.
 ///
 /// Stop hooks run when Claude Code has finished responding. They can
 /// block Claude from stopping and request continuation.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct Stop {
     /// Unique identifier for the current Claude Code session
     pub session_id: String,
     /// Path to the conversation transcript JSON file
     pub transcript_path: String,
+    /// Name of the event, e.g. "Stop". Older Claude Code versions don't send it.
+    #[serde(default, skip_serializing_if = "is_none", alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
     /// True when Claude Code is already continuing as a result of a stop hook.
     /// Check this to prevent infinite loops.
     pub stop_hook_active: bool,
@@ -43,14 +45,39 @@ impl Stop {
     pub fn stop(&self, reason: &str) -> StopOutput {
         StopOutput::default().and_stop(reason)
     }
+
+    /// Check whether a Stop hook already kept Claude going, in which case
+    /// blocking again could loop forever
+    pub fn should_skip(&self) -> bool {
+        self.stop_hook_active
+    }
+
+    /// Allow stopping if a Stop hook is already active, to avoid loops:
+    ///
+    /// ```rust,no_run
+    /// use code_hooks::{HookResponse, Input, Stop};
+    ///
+    /// let input = Stop::read().unwrap();
+    /// if let Some(output) = input.guard_allow() {
+    ///     output.respond();
+    /// }
+    /// ```
+    pub fn guard_allow(&self) -> Option<StopOutput> {
+        self.should_skip().then(|| self.allow())
+    }
+
+    /// Read the transcript and get the text of the last assistant message,
+    /// skipping messages that only use tools
+    pub fn final_assistant_text(&self) -> Result<Option<String>> {
+        Ok(last_assistant_message(&self.read_transcript()?))
+    }
 }
 
 impl Input for Stop {}
 
 impl TranscriptReader for Stop {
-    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
-        let content = fs::read_to_string(&self.transcript_path)?;
-        Ok(parse_transcript(&content)?)
+    fn transcript_path(&self) -> &str {
+        &self.transcript_path
     }
 }
 
@@ -58,6 +85,7 @@ impl TranscriptReader for Stop {
 ///
 /// Controls whether Claude can stop or must continue processing.
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct StopOutput {
     /// Whether to block Claude from stopping.
@@ -82,6 +110,10 @@ pub struct StopOutput {
     /// Hide output from transcript mode (default: false)
     #[serde(skip_serializing_if = "is_none")]
     pub suppress_output: Option<bool>,
+
+    /// Metadata for logs and tooling, ignored by Claude Code
+    #[serde(skip_serializing_if = "is_none")]
+    pub metadata: Option<HookMetadata>,
 }
 
 impl StopOutput {
@@ -101,11 +133,25 @@ impl StopOutput {
         self
     }
 
+    /// Stop Claude from continuing after this hook, showing `reason` to the
+    /// user. An alias for [`and_stop`](Self::and_stop).
+    pub fn and_block_processing(self, reason: &str) -> Self {
+        self.and_stop(reason)
+    }
+
     /// Set whether to suppress output in transcript mode
     pub fn and_suppress_output(mut self, suppress: bool) -> Self {
         self.suppress_output = Some(suppress);
         self
     }
+
+    /// Annotate the response with the id of the rule that produced it
+    pub fn with_rule_id(mut self, rule_id: &str) -> Self {
+        self.metadata = Some(HookMetadata {
+            rule_id: Some(rule_id.to_string()),
+        });
+        self
+    }
 }
 
 impl HookResponse for StopOutput {}
@@ -120,6 +166,7 @@ mod tests {
         let stop = Stop {
             session_id: "test-session".to_string(),
             transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
             stop_hook_active: false,
         };
 
@@ -143,4 +190,78 @@ mod tests {
         assert_eq!(stop_output.continue_, Some(false));
         assert_eq!(stop_output.stop_reason, Some("Task completed".to_string()));
     }
+
+    #[test]
+    fn test_fluent_output() {
+        let output = StopOutput::block("Not allowed")
+            .and_block_processing("Halted by policy")
+            .and_suppress_output(true)
+            .with_rule_id("policy-1");
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({
+                "decision": "block",
+                "reason": "Not allowed",
+                "continue": false,
+                "stopReason": "Halted by policy",
+                "suppressOutput": true,
+                "metadata": {"ruleId": "policy-1"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_guard_allow() {
+        let mut input = Stop {
+            session_id: "s".to_string(),
+            transcript_path: "/t".to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+        assert!(!input.should_skip());
+        assert!(input.guard_allow().is_none());
+
+        input.stop_hook_active = true;
+        assert!(input.should_skip());
+        let output = input.guard_allow().unwrap();
+        assert_eq!(output.decision, None);
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn test_final_assistant_text() {
+        let entry = |uuid: &str, content: &str| {
+            format!(
+                r#"{{"type":"assistant","message":{{"id":"msg_{uuid}","type":"message","role":"assistant","model":"test-model","content":{content},"stop_reason":null,"stop_sequence":null,"usage":{{"input_tokens":1,"output_tokens":1}}}},"uuid":"{uuid}","timestamp":"2025-01-01T00:00:01Z","cwd":"/test","sessionId":"s","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}}"#
+            )
+        };
+        let transcript = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            transcript.path(),
+            [
+                entry("a1", r#"[{"type":"text","text":"Running the tests."}]"#),
+                entry("a2", r#""All tests pass.""#),
+                entry(
+                    "a3",
+                    r#"[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]"#,
+                ),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let input = Stop {
+            session_id: "s".to_string(),
+            transcript_path: transcript.path().to_string_lossy().to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+
+        assert_eq!(
+            input.final_assistant_text().unwrap().as_deref(),
+            Some("All tests pass.")
+        );
+    }
 }
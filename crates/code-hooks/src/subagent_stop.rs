@@ -1,10 +1,8 @@
+use claude_transcript::analysis::last_assistant_message;
 use serde::{Deserialize, Serialize};
-use std::fs;
 
-use crate::Result;
-use crate::io::{Decision, HookResponse, Input, TranscriptReader, is_none};
-use claude_transcript::TranscriptEntry;
-use claude_transcript::parse::parse_transcript;
+use crate::error::Result;
+use crate::io::{Decision, HookMetadata, HookResponse, Input, TranscriptReader, is_none};
 
 /// Input structure for SubagentStop hooks.
 ///
@@ -12,12 +10,16 @@ use claude_transcript::parse::parse_transcript;
 /// block the subagent from stopping and request continuation. This event has
 /// exactly the same semantics as Stop but is only called for subagents.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct SubagentStop {
     /// Unique identifier for the current Claude Code session
     pub session_id: String,
     /// Path to the conversation transcript JSON file
     pub transcript_path: String,
+    /// Name of the event, e.g. "SubagentStop". Older Claude Code versions don't send it.
+    #[serde(default, skip_serializing_if = "is_none", alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
     /// True when the subagent is already continuing as a result of a SubagentStop hook.
     /// Check this to prevent infinite loops.
     pub stop_hook_active: bool,
@@ -44,14 +46,39 @@ impl SubagentStop {
     pub fn stop(&self, reason: &str) -> SubagentStopOutput {
         SubagentStopOutput::default().and_stop(reason)
     }
+
+    /// Check whether a Stop hook already kept the subagent going, in which case
+    /// blocking again could loop forever
+    pub fn should_skip(&self) -> bool {
+        self.stop_hook_active
+    }
+
+    /// Allow stopping if a Stop hook is already active, to avoid loops:
+    ///
+    /// ```rust,no_run
+    /// use code_hooks::{HookResponse, Input, SubagentStop};
+    ///
+    /// let input = SubagentStop::read().unwrap();
+    /// if let Some(output) = input.guard_allow() {
+    ///     output.respond();
+    /// }
+    /// ```
+    pub fn guard_allow(&self) -> Option<SubagentStopOutput> {
+        self.should_skip().then(|| self.allow())
+    }
+
+    /// Read the transcript and get the text of the last assistant message,
+    /// skipping messages that only use tools
+    pub fn final_assistant_text(&self) -> Result<Option<String>> {
+        Ok(last_assistant_message(&self.read_transcript()?))
+    }
 }
 
 impl Input for SubagentStop {}
 
 impl TranscriptReader for SubagentStop {
-    fn read_transcript(&self) -> Result<Vec<TranscriptEntry>> {
-        let content = fs::read_to_string(&self.transcript_path)?;
-        Ok(parse_transcript(&content)?)
+    fn transcript_path(&self) -> &str {
+        &self.transcript_path
     }
 }
 
@@ -59,6 +86,7 @@ impl TranscriptReader for SubagentStop {
 ///
 /// Controls whether the subagent can stop or must continue processing.
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SubagentStopOutput {
     /// Whether to block the subagent from stopping.
@@ -83,6 +111,10 @@ pub struct SubagentStopOutput {
     /// Hide output from transcript mode (default: false)
     #[serde(skip_serializing_if = "is_none")]
     pub suppress_output: Option<bool>,
+
+    /// Metadata for logs and tooling, ignored by Claude Code
+    #[serde(skip_serializing_if = "is_none")]
+    pub metadata: Option<HookMetadata>,
 }
 
 impl SubagentStopOutput {
@@ -102,11 +134,25 @@ impl SubagentStopOutput {
         self
     }
 
+    /// Stop Claude from continuing after this hook, showing `reason` to the
+    /// user. An alias for [`and_stop`](Self::and_stop).
+    pub fn and_block_processing(self, reason: &str) -> Self {
+        self.and_stop(reason)
+    }
+
     /// Set whether to suppress output in transcript mode
     pub fn and_suppress_output(mut self, suppress: bool) -> Self {
         self.suppress_output = Some(suppress);
         self
     }
+
+    /// Annotate the response with the id of the rule that produced it
+    pub fn with_rule_id(mut self, rule_id: &str) -> Self {
+        self.metadata = Some(HookMetadata {
+            rule_id: Some(rule_id.to_string()),
+        });
+        self
+    }
 }
 
 impl HookResponse for SubagentStopOutput {}
@@ -121,6 +167,7 @@ mod tests {
         let subagent_stop = SubagentStop {
             session_id: "test-session".to_string(),
             transcript_path: "/path/to/transcript".to_string(),
+            hook_event_name: None,
             stop_hook_active: false,
         };
 
@@ -144,4 +191,78 @@ mod tests {
         assert_eq!(stop_output.continue_, Some(false));
         assert_eq!(stop_output.stop_reason, Some("Task completed".to_string()));
     }
+
+    #[test]
+    fn test_fluent_output() {
+        let output = SubagentStopOutput::block("Not allowed")
+            .and_block_processing("Halted by policy")
+            .and_suppress_output(true)
+            .with_rule_id("policy-1");
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({
+                "decision": "block",
+                "reason": "Not allowed",
+                "continue": false,
+                "stopReason": "Halted by policy",
+                "suppressOutput": true,
+                "metadata": {"ruleId": "policy-1"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_guard_allow() {
+        let mut input = SubagentStop {
+            session_id: "s".to_string(),
+            transcript_path: "/t".to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+        assert!(!input.should_skip());
+        assert!(input.guard_allow().is_none());
+
+        input.stop_hook_active = true;
+        assert!(input.should_skip());
+        let output = input.guard_allow().unwrap();
+        assert_eq!(output.decision, None);
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn test_final_assistant_text() {
+        let entry = |uuid: &str, content: &str| {
+            format!(
+                r#"{{"type":"assistant","message":{{"id":"msg_{uuid}","type":"message","role":"assistant","model":"test-model","content":{content},"stop_reason":null,"stop_sequence":null,"usage":{{"input_tokens":1,"output_tokens":1}}}},"uuid":"{uuid}","timestamp":"2025-01-01T00:00:01Z","cwd":"/test","sessionId":"s","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}}"#
+            )
+        };
+        let transcript = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            transcript.path(),
+            [
+                entry("a1", r#"[{"type":"text","text":"Running the tests."}]"#),
+                entry("a2", r#""All tests pass.""#),
+                entry(
+                    "a3",
+                    r#"[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]"#,
+                ),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let input = SubagentStop {
+            session_id: "s".to_string(),
+            transcript_path: transcript.path().to_string_lossy().to_string(),
+            hook_event_name: None,
+            stop_hook_active: false,
+        };
+
+        assert_eq!(
+            input.final_assistant_text().unwrap().as_deref(),
+            Some("All tests pass.")
+        );
+    }
 }
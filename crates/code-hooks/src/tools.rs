@@ -0,0 +1,617 @@
+use crate::io::input_str;
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Name of the tool a hook is called for.
+///
+/// Built-in tools have their own variants so hooks can match on them without
+/// comparing strings. Any other name, such as an MCP tool, is kept as `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ToolName {
+    Bash,
+    Edit,
+    MultiEdit,
+    Write,
+    Read,
+    Glob,
+    Grep,
+    LS,
+    WebFetch,
+    WebSearch,
+    Task,
+    TodoWrite,
+    NotebookRead,
+    NotebookEdit,
+    /// Any tool without its own variant
+    Other(String),
+}
+
+impl ToolName {
+    /// The name Claude Code uses for this tool
+    pub fn as_str(&self) -> &str {
+        match self {
+            ToolName::Bash => "Bash",
+            ToolName::Edit => "Edit",
+            ToolName::MultiEdit => "MultiEdit",
+            ToolName::Write => "Write",
+            ToolName::Read => "Read",
+            ToolName::Glob => "Glob",
+            ToolName::Grep => "Grep",
+            ToolName::LS => "LS",
+            ToolName::WebFetch => "WebFetch",
+            ToolName::WebSearch => "WebSearch",
+            ToolName::Task => "Task",
+            ToolName::TodoWrite => "TodoWrite",
+            ToolName::NotebookRead => "NotebookRead",
+            ToolName::NotebookEdit => "NotebookEdit",
+            ToolName::Other(name) => name,
+        }
+    }
+}
+
+impl FromStr for ToolName {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Bash" => ToolName::Bash,
+            "Edit" => ToolName::Edit,
+            "MultiEdit" => ToolName::MultiEdit,
+            "Write" => ToolName::Write,
+            "Read" => ToolName::Read,
+            "Glob" => ToolName::Glob,
+            "Grep" => ToolName::Grep,
+            "LS" => ToolName::LS,
+            "WebFetch" => ToolName::WebFetch,
+            "WebSearch" => ToolName::WebSearch,
+            "Task" => ToolName::Task,
+            "TodoWrite" => ToolName::TodoWrite,
+            "NotebookRead" => ToolName::NotebookRead,
+            "NotebookEdit" => ToolName::NotebookEdit,
+            other => ToolName::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ToolName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ToolName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ToolName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Ok(tool) = String::deserialize(deserializer)?.parse();
+        Ok(tool)
+    }
+}
+
+/// Strongly-typed tool input for the built-in tools.
+///
+/// Use [`ToolInput::parse`] (or `PreToolUse::parsed_tool_input`) to convert the
+/// raw `tool_input` map. Tools that aren't recognized, or whose input doesn't
+/// match the expected shape, are returned as `Other` with the raw map.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolInput {
+    /// Input for the Bash tool
+    Bash {
+        /// The shell command to run
+        command: String,
+        /// Optional description of what the command does
+        description: Option<String>,
+    },
+    /// Input for the Edit tool
+    Edit {
+        /// Path of the file being edited
+        file_path: String,
+        /// Text to replace
+        old_string: String,
+        /// Replacement text
+        new_string: String,
+    },
+    /// Input for the Write tool
+    Write {
+        /// Path of the file being written
+        file_path: String,
+        /// Full contents of the file
+        content: String,
+    },
+    /// Input for the Read tool
+    Read {
+        /// Path of the file being read
+        file_path: String,
+    },
+    /// Input for any other tool, or a known tool with an unexpected shape
+    Other(HashMap<String, Value>),
+}
+
+/// Input for the WebFetch tool
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebFetchInput {
+    /// URL to fetch
+    pub url: String,
+    /// Prompt to run against the fetched content
+    pub prompt: String,
+}
+
+/// Input for the WebSearch tool
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebSearchInput {
+    /// The search query
+    pub query: String,
+    /// Only include results from these domains
+    #[serde(default)]
+    pub allowed_domains: Option<Vec<String>>,
+    /// Never include results from these domains
+    #[serde(default)]
+    pub blocked_domains: Option<Vec<String>>,
+}
+
+/// Response of the WebFetch tool
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebFetchResponse {
+    /// URL that was fetched, which may differ from the requested one after
+    /// redirects
+    #[serde(default)]
+    pub url: Option<String>,
+    /// The result of running the prompt against the fetched content
+    #[serde(alias = "result")]
+    pub content: String,
+    /// HTTP status code
+    #[serde(default)]
+    pub code: Option<u16>,
+}
+
+/// Response of the WebSearch tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebSearchResponse {
+    /// The query that was searched for
+    pub query: Option<String>,
+    /// Search results, in the order returned
+    pub results: Vec<WebSearchResult>,
+}
+
+/// One WebSearch result
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebSearchResult {
+    /// Page title
+    pub title: String,
+    /// Page URL
+    pub url: String,
+}
+
+/// The response of a WebFetch tool call, if the call is one and has the
+/// expected shape
+pub(crate) fn web_fetch_response(
+    tool_name: &str,
+    tool_response: &HashMap<String, Value>,
+) -> Option<WebFetchResponse> {
+    typed_input(tool_name, "WebFetch", tool_response)
+}
+
+/// The response of a WebSearch tool call, if the call is one with a
+/// `results` list.
+///
+/// Results can be listed directly or grouped under a `content` list; entries
+/// without a title and URL, such as text summaries, are skipped.
+pub(crate) fn web_search_response(
+    tool_name: &str,
+    tool_response: &HashMap<String, Value>,
+) -> Option<WebSearchResponse> {
+    if tool_name != "WebSearch" {
+        return None;
+    }
+    let results = tool_response
+        .get("results")?
+        .as_array()?
+        .iter()
+        .flat_map(|item| match item.get("content").and_then(Value::as_array) {
+            Some(content) => content.iter().collect(),
+            None => vec![item],
+        })
+        .filter_map(|result| serde_json::from_value(result.clone()).ok())
+        .collect();
+    Some(WebSearchResponse {
+        query: input_str(tool_response, "query").map(str::to_string),
+        results,
+    })
+}
+
+/// One edit in a MultiEdit tool call
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EditOp {
+    /// Text to replace
+    pub old_string: String,
+    /// Replacement text
+    pub new_string: String,
+    /// Replace every occurrence of `old_string` rather than just one
+    #[serde(default)]
+    pub replace_all: Option<bool>,
+}
+
+/// Input for the MultiEdit tool, of which only the edits are exposed
+#[derive(Deserialize)]
+struct MultiEditInput {
+    edits: Vec<EditOp>,
+}
+
+/// The edits of a MultiEdit tool call, if the call is one and has the expected shape
+pub(crate) fn multi_edits(
+    tool_name: &str,
+    tool_input: &HashMap<String, Value>,
+) -> Option<Vec<EditOp>> {
+    typed_input::<MultiEditInput>(tool_name, "MultiEdit", tool_input).map(|input| input.edits)
+}
+
+/// Tools that never modify files or run commands
+const READ_ONLY_TOOLS: &[&str] = &["Read", "Grep", "Glob", "LS", "NotebookRead"];
+
+/// Programs that only inspect state, whatever their arguments. Programs with
+/// flags that write files or run other programs, such as `rg --pre`,
+/// `tree -o`, `date -s` and `file -C`, are left out.
+const READ_ONLY_PROGRAMS: &[&str] = &[
+    "cat", "ls", "pwd", "echo", "head", "tail", "wc", "grep", "which", "stat", "du", "df", "whoami",
+];
+
+/// Git subcommands that only inspect the repository
+const READ_ONLY_GIT: &[&str] = &[
+    "status",
+    "log",
+    "diff",
+    "show",
+    "blame",
+    "ls-files",
+    "rev-parse",
+];
+
+/// Git flags that write files or run external programs, with or without an
+/// `=value`
+const UNSAFE_GIT_FLAGS: &[&str] = &["--output", "--ext-diff"];
+
+/// Whether a tool call is read-only: a read-only tool, or a Bash command that
+/// passes [`is_read_only_command`]
+pub(crate) fn is_read_only(tool_name: &str, tool_input: &HashMap<String, Value>) -> bool {
+    if READ_ONLY_TOOLS.contains(&tool_name) {
+        return true;
+    }
+    tool_name == "Bash"
+        && tool_input
+            .get("command")
+            .and_then(|c| c.as_str())
+            .is_some_and(is_read_only_command)
+}
+
+/// Best-effort check that a shell command only reads state.
+///
+/// The command is split on `;`, `&` and `|` (which covers `&&`, `||` and
+/// pipes), and every part must start with an allowlisted program such as `cat`,
+/// `ls` or `git status`. Commands with output redirection (`>`), command or
+/// process substitution, subshells or newlines are never considered
+/// read-only, nor are git commands with flags like `--output`. Arguments are
+/// split on whitespace without interpreting quotes, so this rejects some safe
+/// commands but should not accept unsafe ones built from the allowlist.
+pub(crate) fn is_read_only_command(command: &str) -> bool {
+    // `(` covers `$(`, `<(`, `>(` and subshells
+    if command.contains(['>', '`', '\n', '(']) {
+        return false;
+    }
+    command.split([';', '&', '|']).all(|part| {
+        let mut words = part.split_whitespace();
+        match words.next() {
+            // Empty parts come from `&&` and `||`
+            None => true,
+            Some("git") => {
+                words
+                    .next()
+                    .is_some_and(|subcommand| READ_ONLY_GIT.contains(&subcommand))
+                    && !words.any(|word| {
+                        UNSAFE_GIT_FLAGS.iter().any(|flag| {
+                            word.strip_prefix(flag)
+                                .is_some_and(|rest| rest.is_empty() || rest.starts_with('='))
+                        })
+                    })
+            }
+            Some(program) => READ_ONLY_PROGRAMS.contains(&program),
+        }
+    })
+}
+
+/// Deserialize a tool input map into `T` if the call is to the `expected` tool
+pub(crate) fn typed_input<T: DeserializeOwned>(
+    tool_name: &str,
+    expected: &str,
+    tool_input: &HashMap<String, Value>,
+) -> Option<T> {
+    if tool_name != expected {
+        return None;
+    }
+    let value = serde_json::to_value(tool_input).ok()?;
+    serde_json::from_value(value).ok()
+}
+
+/// Mirror of the known `ToolInput` variants, tagged by tool name for deserialization
+#[derive(Deserialize)]
+#[serde(tag = "tool_name", content = "tool_input")]
+enum KnownToolInput {
+    Bash {
+        command: String,
+        #[serde(default)]
+        description: Option<String>,
+    },
+    Edit {
+        file_path: String,
+        old_string: String,
+        new_string: String,
+    },
+    Write {
+        file_path: String,
+        content: String,
+    },
+    Read {
+        file_path: String,
+    },
+}
+
+impl ToolInput {
+    /// Parse a raw tool input map based on the tool name
+    pub fn parse(tool_name: &str, tool_input: &HashMap<String, Value>) -> Self {
+        let tagged = serde_json::json!({
+            "tool_name": tool_name,
+            "tool_input": tool_input,
+        });
+        match serde_json::from_value::<KnownToolInput>(tagged) {
+            Ok(KnownToolInput::Bash {
+                command,
+                description,
+            }) => ToolInput::Bash {
+                command,
+                description,
+            },
+            Ok(KnownToolInput::Edit {
+                file_path,
+                old_string,
+                new_string,
+            }) => ToolInput::Edit {
+                file_path,
+                old_string,
+                new_string,
+            },
+            Ok(KnownToolInput::Write { file_path, content }) => {
+                ToolInput::Write { file_path, content }
+            }
+            Ok(KnownToolInput::Read { file_path }) => ToolInput::Read { file_path },
+            Err(_) => ToolInput::Other(tool_input.clone()),
+        }
+    }
+}
+
+/// Check whether `path` matches any of the glob `patterns`, e.g. `**/*.rs`
+/// or `src/**`, for hooks that only act on some files.
+///
+/// `*` doesn't match `/`, while `**` matches any number of directories.
+/// Invalid patterns never match.
+pub fn path_matches(path: &str, patterns: &[&str]) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    patterns
+        .iter()
+        .filter_map(|pattern| Pattern::new(pattern).ok())
+        .any(|pattern| pattern.matches_with(path, options))
+}
+
+/// Copy a tool input with every substring matching one of `patterns`
+/// replaced by `***`, e.g. to keep secrets in Bash commands out of logs.
+///
+/// Strings nested in arrays and objects are redacted too; keys are kept.
+pub fn redact_tool_input(
+    input: &HashMap<String, Value>,
+    patterns: &[Regex],
+) -> HashMap<String, Value> {
+    input
+        .iter()
+        .map(|(key, value)| (key.clone(), redact_value(value, patterns)))
+        .collect()
+}
+
+fn redact_value(value: &Value, patterns: &[Regex]) -> Value {
+    match value {
+        Value::String(s) => {
+            let redacted = patterns.iter().fold(s.clone(), |s, pattern| {
+                pattern.replace_all(&s, "***").into_owned()
+            });
+            Value::String(redacted)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| redact_value(item, patterns))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), redact_value(value, patterns)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("src/lib.rs", &["**/*.rs"]));
+        assert!(path_matches("lib.rs", &["**/*.rs"]));
+        assert!(path_matches("/home/u/app/src/main.rs", &["**/*.rs"]));
+        assert!(path_matches("src/a/b.txt", &["src/**"]));
+        assert!(!path_matches("src/lib.py", &["**/*.rs"]));
+        assert!(!path_matches("src/lib.rs", &["*.rs"]));
+        assert!(!path_matches("tests/a.txt", &["src/**"]));
+
+        let patterns = ["**/*.rs", "**/Cargo.toml", "docs/**"];
+        assert!(path_matches("crates/x/Cargo.toml", &patterns));
+        assert!(path_matches("docs/guide.md", &patterns));
+        assert!(!path_matches("README.md", &patterns));
+        assert!(!path_matches("src/lib.rs", &[]));
+        assert!(!path_matches("src/lib.rs", &["[", "**/*.py"]));
+    }
+
+    #[test]
+    fn test_redact_tool_input() {
+        let input: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "command": "deploy --token=abc123 --verbose",
+            "nested": {"args": ["--token=abc123"]},
+            "timeout": 10,
+        }))
+        .unwrap();
+        let patterns = [Regex::new(r"--token=\S+").unwrap()];
+
+        let redacted = redact_tool_input(&input, &patterns);
+        assert_eq!(
+            serde_json::to_value(&redacted).unwrap(),
+            serde_json::json!({
+                "command": "deploy *** --verbose",
+                "nested": {"args": ["***"]},
+                "timeout": 10,
+            })
+        );
+        assert_eq!(redact_tool_input(&input, &[]), input);
+    }
+
+    #[test]
+    fn test_tool_name() {
+        assert_eq!("MultiEdit".parse::<ToolName>(), Ok(ToolName::MultiEdit));
+        assert_eq!(
+            "mcp__github__create_issue".parse::<ToolName>(),
+            Ok(ToolName::Other("mcp__github__create_issue".to_string()))
+        );
+        assert_eq!(
+            serde_json::from_value::<ToolName>(Value::String("Grep".to_string())).unwrap(),
+            ToolName::Grep
+        );
+        assert_eq!(ToolName::Other("Custom".to_string()).to_string(), "Custom");
+        assert_eq!(
+            serde_json::to_value(ToolName::WebFetch).unwrap(),
+            Value::String("WebFetch".to_string())
+        );
+    }
+
+    fn input(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_known_tools() {
+        let bash = ToolInput::parse("Bash", &input(serde_json::json!({"command": "ls"})));
+        assert_eq!(
+            bash,
+            ToolInput::Bash {
+                command: "ls".to_string(),
+                description: None
+            }
+        );
+
+        let edit = ToolInput::parse(
+            "Edit",
+            &input(serde_json::json!({
+                "file_path": "/tmp/a.rs",
+                "old_string": "a",
+                "new_string": "b",
+                "replace_all": false
+            })),
+        );
+        assert_eq!(
+            edit,
+            ToolInput::Edit {
+                file_path: "/tmp/a.rs".to_string(),
+                old_string: "a".to_string(),
+                new_string: "b".to_string()
+            }
+        );
+
+        let read = ToolInput::parse("Read", &input(serde_json::json!({"file_path": "/tmp/a"})));
+        assert_eq!(
+            read,
+            ToolInput::Read {
+                file_path: "/tmp/a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_falls_back_to_other() {
+        // Unknown tool
+        let raw = input(serde_json::json!({"pattern": "*.rs"}));
+        assert_eq!(
+            ToolInput::parse("Glob", &raw),
+            ToolInput::Other(raw.clone())
+        );
+
+        // Known tool with a mismatched shape
+        let raw = input(serde_json::json!({"file_path": "/tmp/a"}));
+        assert_eq!(
+            ToolInput::parse("Write", &raw),
+            ToolInput::Other(raw.clone())
+        );
+    }
+
+    #[test]
+    fn test_is_read_only_command() {
+        assert!(is_read_only_command("git status"));
+        assert!(is_read_only_command("ls -la && cat Cargo.toml | head -5"));
+        assert!(!is_read_only_command("git push"));
+        assert!(!is_read_only_command("echo hi > out.txt"));
+        assert!(!is_read_only_command("cat $(rm -rf /)"));
+        assert!(!is_read_only_command("ls; rm file.txt"));
+        assert!(!is_read_only_command("cat <(rm -rf ~)"));
+        assert!(!is_read_only_command("cat >(rm -rf ~)"));
+        assert!(!is_read_only_command("(rm -rf ~)"));
+        assert!(!is_read_only_command("rg --pre sh x f"));
+        assert!(!is_read_only_command("tree -o ~/.bashrc"));
+        assert!(!is_read_only_command("date -s 2000-01-01"));
+        assert!(!is_read_only_command("file -C -m magic"));
+        assert!(!is_read_only_command("git diff --output=f"));
+        assert!(!is_read_only_command("git log --output f"));
+        assert!(!is_read_only_command("git diff --ext-diff"));
+        assert!(is_read_only_command("git log --oneline"));
+    }
+
+    #[test]
+    fn test_typed_input() {
+        let raw =
+            input(serde_json::json!({"query": "rust serde", "blocked_domains": ["example.com"]}));
+        assert_eq!(
+            typed_input::<WebSearchInput>("WebSearch", "WebSearch", &raw),
+            Some(WebSearchInput {
+                query: "rust serde".to_string(),
+                allowed_domains: None,
+                blocked_domains: Some(vec!["example.com".to_string()]),
+            })
+        );
+        assert_eq!(
+            typed_input::<WebSearchInput>("Bash", "WebSearch", &raw),
+            None
+        );
+        assert_eq!(
+            typed_input::<WebFetchInput>("WebFetch", "WebFetch", &raw),
+            None
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+use crate::io::{Decision, HookMetadata, HookResponse, Input, TranscriptReader, is_none};
+
+/// Input structure for UserPromptSubmit hooks.
+///
+/// UserPromptSubmit hooks run when the user submits a prompt, before Claude
+/// processes it. They can add context to the prompt or block it entirely.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub struct UserPromptSubmit {
+    /// Unique identifier for the current Claude Code session
+    pub session_id: String,
+    /// Path to the conversation transcript JSON file
+    pub transcript_path: String,
+    /// Name of the event, e.g. "UserPromptSubmit". Older Claude Code versions don't send it.
+    #[serde(default, skip_serializing_if = "is_none", alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
+    /// The prompt text the user submitted
+    pub prompt: String,
+}
+
+impl UserPromptSubmit {
+    /// Create a response that lets the prompt through unchanged
+    pub fn passthrough(&self) -> UserPromptSubmitOutput {
+        UserPromptSubmitOutput::default()
+    }
+
+    /// Create a block response that prevents the prompt from being processed
+    pub fn block(&self, reason: &str) -> UserPromptSubmitOutput {
+        UserPromptSubmitOutput::block(reason)
+    }
+
+    /// Create a response that replaces the prompt with `new_prompt`
+    ///
+    /// See [`UserPromptSubmitOutput::rewrite`].
+    pub fn rewrite(&self, new_prompt: &str) -> UserPromptSubmitOutput {
+        UserPromptSubmitOutput::rewrite(new_prompt)
+    }
+}
+
+impl Input for UserPromptSubmit {}
+
+impl TranscriptReader for UserPromptSubmit {
+    fn transcript_path(&self) -> &str {
+        &self.transcript_path
+    }
+}
+
+/// Hook-specific output for UserPromptSubmit hooks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UserPromptSubmitHookSpecificOutput {
+    /// Always "UserPromptSubmit"
+    pub hook_event_name: String,
+
+    /// Text added to the context alongside the prompt
+    #[serde(skip_serializing_if = "is_none")]
+    pub additional_context: Option<String>,
+}
+
+/// Output structure for UserPromptSubmit hooks.
+///
+/// Controls whether the prompt is processed and what extra context Claude sees.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UserPromptSubmitOutput {
+    /// Whether to block the prompt.
+    /// - `Block`: The prompt is not processed and is erased from context
+    /// - `None`: The prompt is processed normally
+    #[serde(skip_serializing_if = "is_none")]
+    pub decision: Option<Decision>,
+
+    /// Shown to the user when decision is Block
+    #[serde(skip_serializing_if = "is_none")]
+    pub reason: Option<String>,
+
+    /// Whether Claude should continue after hook execution (default: true).
+    /// Takes precedence over decision if set to false.
+    #[serde(rename = "continue", skip_serializing_if = "is_none")]
+    pub continue_: Option<bool>,
+
+    /// Message shown to user when continue is false
+    #[serde(skip_serializing_if = "is_none")]
+    pub stop_reason: Option<String>,
+
+    /// Hide output from transcript mode (default: false)
+    #[serde(skip_serializing_if = "is_none")]
+    pub suppress_output: Option<bool>,
+
+    /// Hook-specific fields, such as additional context
+    #[serde(skip_serializing_if = "is_none")]
+    pub hook_specific_output: Option<UserPromptSubmitHookSpecificOutput>,
+
+    /// Metadata for logs and tooling, ignored by Claude Code
+    #[serde(skip_serializing_if = "is_none")]
+    pub metadata: Option<HookMetadata>,
+}
+
+impl UserPromptSubmitOutput {
+    /// Create a block response that prevents the prompt from being processed
+    pub fn block(reason: &str) -> Self {
+        Self {
+            decision: Some(Decision::Block),
+            reason: Some(reason.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a response that replaces the original prompt with `new_prompt`
+    ///
+    /// Claude Code has no field for updating a prompt in place, so this lets
+    /// the original through with additional context instructing Claude to
+    /// respond to `new_prompt` instead. It doesn't block, since Claude Code
+    /// drops both a blocked prompt and its additional context.
+    pub fn rewrite(new_prompt: &str) -> Self {
+        Self::default().with_additional_context(&format!(
+            "The user's prompt was rewritten by a hook. Respond to this prompt instead:\n\n{new_prompt}"
+        ))
+    }
+
+    /// Add context that Claude sees alongside the prompt
+    pub fn with_additional_context(mut self, context: &str) -> Self {
+        self.hook_specific_output = Some(UserPromptSubmitHookSpecificOutput {
+            hook_event_name: "UserPromptSubmit".to_string(),
+            additional_context: Some(context.to_string()),
+        });
+        self
+    }
+
+    /// Set the continue field to false and provide a stop reason
+    pub fn and_stop(mut self, reason: &str) -> Self {
+        self.continue_ = Some(false);
+        self.stop_reason = Some(reason.to_string());
+        self
+    }
+
+    /// Stop Claude from continuing after this hook, showing `reason` to the
+    /// user. An alias for [`and_stop`](Self::and_stop).
+    pub fn and_block_processing(self, reason: &str) -> Self {
+        self.and_stop(reason)
+    }
+
+    /// Set whether to suppress output in transcript mode
+    pub fn and_suppress_output(mut self, suppress: bool) -> Self {
+        self.suppress_output = Some(suppress);
+        self
+    }
+
+    /// Annotate the response with the id of the rule that produced it
+    pub fn with_rule_id(mut self, rule_id: &str) -> Self {
+        self.metadata = Some(HookMetadata {
+            rule_id: Some(rule_id.to_string()),
+        });
+        self
+    }
+}
+
+impl HookResponse for UserPromptSubmitOutput {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_prompt_submit_roundtrip() {
+        let json = r#"{"session_id":"test-session","transcript_path":"/path/to/transcript","prompt":"fix the bug"}"#;
+        let input: UserPromptSubmit = serde_json::from_str(json).unwrap();
+        assert_eq!(input.prompt, "fix the bug");
+
+        let passthrough = input.passthrough();
+        assert_eq!(serde_json::to_string(&passthrough).unwrap(), "{}");
+
+        let block = input.block("Not allowed");
+        let deserialized: UserPromptSubmitOutput =
+            serde_json::from_str(&serde_json::to_string(&block).unwrap()).unwrap();
+        assert_eq!(deserialized.decision, Some(Decision::Block));
+        assert_eq!(deserialized.reason, Some("Not allowed".to_string()));
+    }
+
+    #[test]
+    fn test_rewrite_serialization() {
+        let output = UserPromptSubmitOutput::rewrite("Fix the failing test in parse.rs");
+        let value = serde_json::to_value(&output).unwrap();
+        assert!(value.get("decision").is_none());
+        assert!(value.get("reason").is_none());
+        assert_eq!(
+            value["hookSpecificOutput"]["hookEventName"],
+            "UserPromptSubmit"
+        );
+        assert!(
+            value["hookSpecificOutput"]["additionalContext"]
+                .as_str()
+                .unwrap()
+                .ends_with("\n\nFix the failing test in parse.rs")
+        );
+    }
+}
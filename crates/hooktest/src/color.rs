@@ -1,4 +1,7 @@
 use anyhow::Result;
+use std::cell::Cell;
+use std::ffi::OsString;
+use std::io::{self, Write};
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Style, ThemeSet};
 use syntect::parsing::SyntaxSet;
@@ -26,39 +29,142 @@ impl ColorMode {
         match self {
             ColorMode::Always => true,
             ColorMode::Never => false,
-            ColorMode::Auto => atty::is(atty::Stream::Stdout),
+            ColorMode::Auto => auto_colorize(
+                std::env::var_os("NO_COLOR"),
+                std::env::var_os("CLICOLOR_FORCE"),
+                atty::is(atty::Stream::Stdout),
+            ),
         }
     }
 }
 
+/// Decide whether to colorize without an explicit flag. A non-empty `NO_COLOR`
+/// disables color, then a `CLICOLOR_FORCE` other than "0" enables it, and
+/// otherwise color is used only on a tty.
+fn auto_colorize(
+    no_color: Option<OsString>,
+    clicolor_force: Option<OsString>,
+    is_tty: bool,
+) -> bool {
+    if no_color.is_some_and(|v| !v.is_empty()) {
+        false
+    } else if clicolor_force.is_some_and(|v| v != "0") {
+        true
+    } else {
+        is_tty
+    }
+}
+
+/// Theme used for highlighting JSON
+const THEME: &str = "base16-ocean.dark";
+
 pub struct JsonHighlighter {
     ps: SyntaxSet,
     ts: ThemeSet,
     enabled: bool,
+    warned: Cell<bool>,
 }
 
 impl JsonHighlighter {
     pub fn new(color_mode: ColorMode) -> Self {
+        Self::with_syntax_set(SyntaxSet::load_defaults_newlines(), color_mode)
+    }
+
+    /// Create a highlighter using a specific syntax set. If the set has no JSON
+    /// syntax, JSON is printed plain.
+    pub fn with_syntax_set(ps: SyntaxSet, color_mode: ColorMode) -> Self {
         Self {
-            ps: SyntaxSet::load_defaults_newlines(),
+            ps,
             ts: ThemeSet::load_defaults(),
             enabled: color_mode.should_colorize(),
+            warned: Cell::new(false),
         }
     }
 
     pub fn print_json(&self, json: &str) -> Result<()> {
-        if self.enabled {
-            let syntax = self.ps.find_syntax_by_extension("json").unwrap();
-            let mut h = HighlightLines::new(syntax, &self.ts.themes["base16-ocean.dark"]);
-
-            for line in json.lines() {
-                let ranges: Vec<(Style, &str)> = h.highlight_line(line, &self.ps)?;
-                let escaped = as_24_bit_terminal_escaped(&ranges[..], false);
-                println!("{escaped}");
+        self.write_json(&mut io::stdout(), json)
+    }
+
+    /// Write JSON to `out`, highlighted if color is enabled and JSON syntax is
+    /// available
+    pub fn write_json<W: Write>(&self, out: &mut W, json: &str) -> Result<()> {
+        let highlighting = self.enabled.then(|| {
+            self.ps
+                .find_syntax_by_extension("json")
+                .zip(self.ts.themes.get(THEME))
+        });
+        match highlighting {
+            Some(Some((syntax, theme))) => {
+                let mut h = HighlightLines::new(syntax, theme);
+                for line in json.lines() {
+                    let ranges: Vec<(Style, &str)> = h.highlight_line(line, &self.ps)?;
+                    let escaped = as_24_bit_terminal_escaped(&ranges[..], false);
+                    writeln!(out, "{escaped}")?;
+                }
             }
-        } else {
-            print!("{json}");
+            Some(None) => {
+                if !self.warned.replace(true) {
+                    eprintln!("Warning: JSON syntax highlighting unavailable, printing plain JSON");
+                }
+                write!(out, "{json}")?;
+            }
+            None => write!(out, "{json}")?,
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(value: &str) -> Option<OsString> {
+        Some(value.into())
+    }
+
+    #[test]
+    fn test_auto_colorize_env() {
+        assert!(auto_colorize(None, None, true));
+        assert!(!auto_colorize(None, None, false));
+
+        assert!(!auto_colorize(var("1"), None, true));
+        assert!(auto_colorize(var(""), None, true));
+
+        assert!(auto_colorize(None, var("1"), false));
+        assert!(!auto_colorize(None, var("0"), false));
+        assert!(!auto_colorize(var("1"), var("1"), true));
+    }
+
+    #[test]
+    fn test_flags_take_priority() {
+        assert!(matches!(
+            ColorMode::from_flags(true, false),
+            ColorMode::Always
+        ));
+        assert!(matches!(
+            ColorMode::from_flags(false, true),
+            ColorMode::Never
+        ));
+        assert!(ColorMode::Always.should_colorize());
+        assert!(!ColorMode::Never.should_colorize());
+    }
+
+    #[test]
+    fn test_missing_json_syntax_falls_back_to_plain() {
+        let highlighter = JsonHighlighter::with_syntax_set(SyntaxSet::new(), ColorMode::Always);
+        let mut out = Vec::new();
+        highlighter.write_json(&mut out, "{\"a\": 1}\n").unwrap();
+        highlighter.write_json(&mut out, "{}\n").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "{\"a\": 1}\n{}\n");
+        assert!(highlighter.warned.get());
+    }
+
+    #[test]
+    fn test_default_syntax_set_highlights() {
+        let highlighter = JsonHighlighter::new(ColorMode::Always);
+        let mut out = Vec::new();
+        highlighter.write_json(&mut out, "{\"a\": 1}").unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("\x1b["));
+        assert!(!highlighter.warned.get());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::color::ColorMode;
+use crate::execute::{ExecOptions, HookExecution, execute_hook};
+use anyhow::Result;
+use code_hooks::{EventKind, PreToolUse};
+use serde_json::Value;
+use std::collections::HashMap;
+use termcolor::Color;
+
+/// Run a PreToolUse hook and check its output reads the same under the legacy
+/// (`decision`) and new (`hookSpecificOutput.permissionDecision`) protocols.
+pub fn run_compat(
+    tool_name: String,
+    tool_input: HashMap<String, Value>,
+    opts: &ExecOptions,
+    color_mode: ColorMode,
+) -> Result<HookExecution> {
+    let mut out = opts.output(color_mode);
+
+    let hook_input = PreToolUse {
+        session_id: "hooktest-compat".to_string(),
+        transcript_path: "/tmp/transcript.json".to_string(),
+        hook_event_name: Some(EventKind::PreToolUse.hook_event_name().to_string()),
+        tool_name,
+        tool_input,
+        permission_mode: None,
+    };
+    let input_json = opts.input_json(&hook_input)?;
+    let input_value: Value = serde_json::from_str(&input_json)?;
+
+    let execution = execute_hook(&mut out, opts, &input_json, &input_value)?;
+    if execution.timed_out {
+        return Ok(execution);
+    }
+
+    out.h1("Protocol Compatibility")?;
+    let Some(hook_output) = &execution.parsed_output else {
+        out.dimmed("No JSON output: both protocols fall back to the exit code")?;
+        return Ok(execution);
+    };
+
+    let legacy = hook_output.get("decision").and_then(|d| d.as_str());
+    let new = hook_output
+        .pointer("/hookSpecificOutput/permissionDecision")
+        .and_then(|d| d.as_str());
+
+    // Older versions only read `decision`. Newer versions prefer
+    // `permissionDecision` and map a legacy decision onto it.
+    let legacy_view = legacy.unwrap_or("none");
+    let new_view = new.or(legacy.map(legacy_to_new)).unwrap_or("none");
+    out.label("Legacy protocol sees", legacy_view)?;
+    out.label("New protocol sees", new_view)?;
+
+    let warnings = compat_warnings(legacy, new);
+    if warnings.is_empty() {
+        out.success("✓ Output is understood the same way by legacy and new Claude Code versions")?;
+        out.newline()?;
+    }
+    for warning in warnings {
+        out.color(&format!("⚠ {warning}"), Color::Yellow, true)?;
+        out.newline()?;
+    }
+
+    Ok(execution)
+}
+
+/// The new-protocol equivalent of a legacy decision; other decisions are
+/// returned unchanged
+pub(crate) fn legacy_to_new(decision: &str) -> &str {
+    match decision {
+        "approve" => "allow",
+        "block" => "deny",
+        other => other,
+    }
+}
+
+/// Explain any way the two protocols would disagree about this output
+fn compat_warnings(legacy: Option<&str>, new: Option<&str>) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if let Some(legacy) = legacy
+        && !matches!(legacy, "approve" | "block")
+    {
+        warnings.push(format!(
+            "Unknown legacy decision '{legacy}' (expected approve or block)"
+        ));
+    }
+    if let Some(new) = new
+        && !matches!(new, "allow" | "deny" | "ask")
+    {
+        warnings.push(format!(
+            "Unknown permissionDecision '{new}' (expected allow, deny or ask)"
+        ));
+    }
+
+    match (legacy, new) {
+        (None, Some("ask")) => warnings.push(
+            "permissionDecision 'ask' has no legacy equivalent: older versions follow the normal permission flow".to_string(),
+        ),
+        (None, Some(new)) => warnings.push(format!(
+            "Older Claude Code versions ignore hookSpecificOutput and see no decision: also set decision to '{}'",
+            new_to_legacy(new)
+        )),
+        (Some(legacy), Some(new)) if legacy_to_new(legacy) != new => warnings.push(format!(
+            "Ambiguous output: decision '{legacy}' and permissionDecision '{new}' disagree, so behavior depends on the Claude Code version"
+        )),
+        _ => {}
+    }
+
+    warnings
+}
+
+/// The legacy equivalent of a new-protocol decision
+fn new_to_legacy(decision: &str) -> &str {
+    match decision {
+        "allow" => "approve",
+        "deny" => "block",
+        other => other,
+    }
+}
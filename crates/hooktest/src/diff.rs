@@ -0,0 +1,120 @@
+use crate::output::Output;
+use anyhow::Result;
+use serde_json::Value;
+use termcolor::Color;
+
+/// One line of a line-by-line diff
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Diff two strings line by line, using the longest common subsequence
+pub fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    lines
+}
+
+/// Render the `old_string`/`new_string` of an Edit or MultiEdit call as a
+/// diff, returning false if the input isn't one
+pub fn render_edit_diff(out: &mut Output, hook_input: &Value) -> Result<bool> {
+    let tool_name = hook_input.get("tool_name").and_then(|t| t.as_str());
+    let Some(tool_input) = hook_input.get("tool_input") else {
+        return Ok(false);
+    };
+    let edits: Vec<&Value> = match tool_name {
+        Some("Edit") => vec![tool_input],
+        Some("MultiEdit") => tool_input
+            .get("edits")
+            .and_then(|e| e.as_array())
+            .map(|edits| edits.iter().collect())
+            .unwrap_or_default(),
+        _ => return Ok(false),
+    };
+    let pairs: Vec<(&str, &str)> = edits
+        .iter()
+        .filter_map(|edit| {
+            let old = edit.get("old_string")?.as_str()?;
+            let new = edit.get("new_string")?.as_str()?;
+            Some((old, new))
+        })
+        .collect();
+    if pairs.is_empty() {
+        return Ok(false);
+    }
+
+    out.h1("Edit Diff")?;
+    if let Some(path) = tool_input.get("file_path").and_then(|p| p.as_str()) {
+        out.label("File", path)?;
+    }
+    for (index, (old, new)) in pairs.iter().enumerate() {
+        if pairs.len() > 1 {
+            out.dimmed(&format!("@@ edit {} @@", index + 1))?;
+        }
+        for line in line_diff(old, new) {
+            match line {
+                DiffLine::Same(text) => out.writeln(&format!("  {text}"))?,
+                DiffLine::Removed(text) => {
+                    out.color_line(&format!("- {text}"), Color::Red, false)?
+                }
+                DiffLine::Added(text) => {
+                    out.color_line(&format!("+ {text}"), Color::Green, false)?
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(
+            line_diff("a\nb\nc", "a\nx\nc\nd"),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+        assert_eq!(line_diff("", "new"), vec![DiffLine::Added("new")]);
+    }
+}
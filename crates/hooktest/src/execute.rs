@@ -1,17 +1,217 @@
+use crate::color::ColorMode;
+use crate::expect::Expectations;
 use crate::output::Output;
+use crate::render_spec::RenderSpec;
 use anyhow::Result;
-use std::io::Write;
-use std::process::{Command, Stdio};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How hooktest reports results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable report
+    Human,
+    /// A single JSON object describing the execution
+    Json,
+}
+
+/// Options for running a hook under test, shared by the event subcommands.
+pub struct ExecOptions {
+    /// Hook command and arguments
+    pub hook_args: Vec<String>,
+    /// Captured payload to send verbatim instead of the generated input
+    pub raw_input: Option<String>,
+    /// Kill the hook if it runs longer than this
+    pub timeout: Option<Duration>,
+    /// How results are reported
+    pub format: OutputFormat,
+    /// Outcomes to assert on after the hook runs
+    pub expect: Expectations,
+    /// Descriptions of custom output fields to explain
+    pub render_spec: Option<RenderSpec>,
+    /// Extra environment variables for the hook process
+    pub env: Vec<(String, String)>,
+    /// Show the input JSON on a single line
+    pub compact_input: bool,
+    /// Let the hook write to hooktest's stderr directly instead of capturing it
+    pub stream: bool,
+    /// Leave the input section out of the report
+    pub no_input_echo: bool,
+    /// Run the hook this many times and report timing instead of its output
+    pub repeat: Option<u32>,
+}
+
+impl ExecOptions {
+    /// The JSON to send to the hook: the captured payload if one was given,
+    /// otherwise `hook_input` serialized
+    pub fn input_json<T: Serialize>(&self, hook_input: &T) -> Result<String> {
+        match &self.raw_input {
+            Some(raw) => Ok(raw.clone()),
+            None => Ok(serde_json::to_string(hook_input)?),
+        }
+    }
+
+    /// The output to write the human-readable report to, which discards
+    /// everything when reporting JSON
+    pub fn output(&self, color_mode: ColorMode) -> Output {
+        match self.format {
+            OutputFormat::Human => Output::new(color_mode),
+            OutputFormat::Json => Output::quiet(),
+        }
+    }
+
+    /// Print the "Input JSON" section, compact if requested
+    pub fn show_input(&self, out: &mut Output, hook_input: &serde_json::Value) -> Result<()> {
+        out.h1("Input JSON")?;
+        if self.compact_input {
+            out.json_compact(hook_input)
+        } else {
+            out.json(hook_input)
+        }
+    }
+
+    /// Explain custom output fields using the render spec, if one was given
+    pub fn annotate_output(&self, out: &mut Output, hook_output: &serde_json::Value) -> Result<()> {
+        match &self.render_spec {
+            Some(spec) => spec.annotate(out, hook_output),
+            None => Ok(()),
+        }
+    }
+
+    /// Print the execution as JSON if requested, and fail if the hook timed
+    /// out or didn't meet the expectations
+    pub fn finish(&self, execution: &HookExecution) -> Result<()> {
+        if self.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string(execution)?);
+        }
+        if let Some(failure) = self.failure(execution) {
+            anyhow::bail!("{failure}");
+        }
+        Ok(())
+    }
+
+    /// Why the execution counts as a failure: a timeout or unmet expectations
+    pub fn failure(&self, execution: &HookExecution) -> Option<String> {
+        if execution.timed_out {
+            let seconds = self.timeout.unwrap_or_default().as_secs_f64();
+            return Some(format!("Hook timed out after {seconds}s"));
+        }
+        let mismatches = self.expect.check(execution);
+        (!mismatches.is_empty()).then(|| mismatches.join("; "))
+    }
+}
+
+/// Parse an `--env KEY=VALUE` argument
+pub fn parse_env(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{s}'")),
+    }
+}
+
+/// The result of running a hook, as reported by `--format json`
+#[derive(Debug, Serialize)]
+pub struct HookExecution {
+    /// The JSON input sent to the hook
+    pub input: serde_json::Value,
+    /// Exit code, or `None` if the hook was killed by a signal or timed out
+    pub exit_code: Option<i32>,
+    /// Whether the hook was killed for exceeding the timeout
+    pub timed_out: bool,
+    /// Everything the hook wrote to stdout
+    pub stdout: String,
+    /// Everything the hook wrote to stderr, empty with `--stream`
+    pub stderr: String,
+    /// The hook's stdout parsed as JSON, if it exited successfully with valid JSON
+    pub parsed_output: Option<serde_json::Value>,
+    /// The decision from the parsed output, preferring
+    /// `hookSpecificOutput.permissionDecision` over the legacy `decision`
+    pub decision: Option<String>,
+    /// How long the runs took, with `--repeat`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<TimingStats>,
+}
+
+/// Durations of repeated runs of a hook, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimingStats {
+    pub runs: usize,
+    /// Runs that exited with a non-zero status
+    pub failed: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+}
+
+impl TimingStats {
+    /// Summarize the durations of `failed` failing runs out of `durations`,
+    /// which must not be empty
+    fn new(durations: &[Duration], failed: usize) -> Self {
+        let mut ms: Vec<f64> = durations.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let mid = ms.len() / 2;
+        let median_ms = if ms.len().is_multiple_of(2) {
+            (ms[mid - 1] + ms[mid]) / 2.0
+        } else {
+            ms[mid]
+        };
+        Self {
+            runs: ms.len(),
+            failed,
+            min_ms: ms[0],
+            median_ms,
+            max_ms: ms[ms.len() - 1],
+            mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+        }
+    }
+}
+
+impl HookExecution {
+    fn new(input: &serde_json::Value) -> Self {
+        Self {
+            input: input.clone(),
+            exit_code: None,
+            timed_out: false,
+            stdout: String::new(),
+            stderr: String::new(),
+            parsed_output: None,
+            decision: None,
+            timing: None,
+        }
+    }
+}
 
 /// Spawn a hook process, feed it the given JSON input, and print execution details.
 ///
-/// Returns the parsed JSON output if the process succeeded and produced valid JSON.
+/// The returned execution holds the parsed JSON output if the process succeeded
+/// and produced valid JSON. If `opts.timeout` elapses first, the hook is killed
+/// and the execution is marked as timed out.
 pub fn execute_hook(
     out: &mut Output,
-    hook_args: &[String],
+    opts: &ExecOptions,
     input_json: &str,
     hook_input_value: &serde_json::Value,
-) -> Result<Option<serde_json::Value>> {
+) -> Result<HookExecution> {
+    execute_hook_with_input_view(out, opts, input_json, hook_input_value, |out| {
+        opts.show_input(out, hook_input_value)
+    })
+}
+
+/// Like [`execute_hook`], with `show_input` rendering the input section
+/// instead of the JSON view
+pub fn execute_hook_with_input_view(
+    out: &mut Output,
+    opts: &ExecOptions,
+    input_json: &str,
+    hook_input_value: &serde_json::Value,
+    show_input: impl FnOnce(&mut Output) -> Result<()>,
+) -> Result<HookExecution> {
+    let mut execution = HookExecution::new(hook_input_value);
+    let hook_args = &opts.hook_args;
     if hook_args.is_empty() {
         anyhow::bail!("No hook command provided. Use -- followed by the hook command.");
     }
@@ -21,29 +221,49 @@ pub fn execute_hook(
         cmd.args(&hook_args[1..]);
     }
 
+    // Claude Code runs hooks with the project directory in CLAUDE_PROJECT_DIR
+    if !opts.env.iter().any(|(key, _)| key == "CLAUDE_PROJECT_DIR") {
+        cmd.env("CLAUDE_PROJECT_DIR", std::env::current_dir()?);
+    }
+    cmd.envs(opts.env.iter().map(|(key, value)| (key, value)));
+
     out.h1("Running Hook")?;
     out.label(
         "Command",
         &format!("{} {}", hook_args[0], hook_args[1..].join(" ")),
     )?;
 
-    out.h1("Input JSON")?;
-    out.json(hook_input_value)?;
-
-    out.h1("Execution")?;
+    if !opts.no_input_echo {
+        show_input(out)?;
+    }
 
-    let mut child = cmd
-        .stdin(Stdio::piped())
+    // A streamed stderr isn't captured, so it's absent from the report
+    let stderr = if opts.stream {
+        Stdio::inherit()
+    } else {
+        Stdio::piped()
+    };
+    cmd.stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()?;
+        .stderr(stderr);
 
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin.write_all(input_json.as_bytes())?;
-        stdin.flush()?;
+    if let Some(runs) = opts.repeat {
+        return repeat_hook(out, opts, &mut cmd, input_json, execution, runs);
     }
 
-    let output = child.wait_with_output()?;
+    out.h1("Execution")?;
+
+    let Some(output) = run_once(&mut cmd, input_json, opts.timeout)? else {
+        let seconds = opts.timeout.unwrap_or_default().as_secs_f64();
+        out.error(&format!("✗ Hook timed out after {seconds}s and was killed"))?;
+        out.newline()?;
+        execution.timed_out = true;
+        return Ok(execution);
+    };
+
+    execution.exit_code = output.status.code();
+    execution.stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    execution.stderr = String::from_utf8_lossy(&output.stderr).into_owned();
 
     let exit_code = output.status.code().unwrap_or(-1);
     if output.status.success() {
@@ -71,7 +291,8 @@ pub fn execute_hook(
             Ok(json) => {
                 out.h1("Hook Output (Parsed)")?;
                 out.json(&json)?;
-                return Ok(Some(json));
+                execution.decision = decision_of(&json);
+                execution.parsed_output = Some(json);
             }
             Err(e) => {
                 out.h1("Hook Output (Raw - Failed to parse)")?;
@@ -82,5 +303,166 @@ pub fn execute_hook(
         }
     }
 
-    Ok(None)
+    Ok(execution)
+}
+
+/// Run the hook `runs` times without showing its output, then report how
+/// long the runs took.
+///
+/// The returned execution describes the last run, so expectations are checked
+/// against it. Repetition stops at the first run that times out.
+fn repeat_hook(
+    out: &mut Output,
+    opts: &ExecOptions,
+    cmd: &mut Command,
+    input_json: &str,
+    mut execution: HookExecution,
+    runs: u32,
+) -> Result<HookExecution> {
+    out.h1("Timing")?;
+
+    let mut durations = Vec::new();
+    let mut failed = 0;
+    let mut last = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let Some(output) = run_once(cmd, input_json, opts.timeout)? else {
+            let seconds = opts.timeout.unwrap_or_default().as_secs_f64();
+            out.error(&format!(
+                "✗ Run {} timed out after {seconds}s and was killed",
+                durations.len() + 1
+            ))?;
+            out.newline()?;
+            execution.timed_out = true;
+            return Ok(execution);
+        };
+        durations.push(start.elapsed());
+        if !output.status.success() {
+            failed += 1;
+        }
+        last = Some(output);
+    }
+
+    let timing = TimingStats::new(&durations, failed);
+    out.label("Runs", &timing.runs.to_string())?;
+    if failed > 0 {
+        out.error(&format!("✗ {failed} of {} runs failed", timing.runs))?;
+        out.newline()?;
+    }
+    out.label(
+        "Duration",
+        &format!(
+            "min {:.2}ms, median {:.2}ms, max {:.2}ms, mean {:.2}ms",
+            timing.min_ms, timing.median_ms, timing.max_ms, timing.mean_ms
+        ),
+    )?;
+
+    if let Some(output) = last {
+        execution.exit_code = output.status.code();
+        execution.stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        execution.stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if output.status.success() {
+            execution.parsed_output = serde_json::from_slice(&output.stdout).ok();
+            execution.decision = execution.parsed_output.as_ref().and_then(decision_of);
+        }
+    }
+    execution.timing = Some(timing);
+    Ok(execution)
+}
+
+/// The decision in a hook's output, preferring
+/// `hookSpecificOutput.permissionDecision` over the legacy `decision`
+fn decision_of(output: &serde_json::Value) -> Option<String> {
+    output
+        .pointer("/hookSpecificOutput/permissionDecision")
+        .or_else(|| output.get("decision"))
+        .and_then(|d| d.as_str())
+        .map(str::to_string)
+}
+
+/// Spawn the hook, write `input_json` to its stdin and wait for it to exit.
+///
+/// Returns `None` if the hook was killed for exceeding `timeout`.
+fn run_once(
+    cmd: &mut Command,
+    input_json: &str,
+    timeout: Option<Duration>,
+) -> Result<Option<HookOutput>> {
+    let mut child = cmd.spawn()?;
+
+    // Write the input on its own thread, so a hook that doesn't read it, or
+    // fills its stdout first, can't block us before the deadline starts
+    let writer = child.stdin.take().map(|mut stdin| {
+        let input = input_json.to_string();
+        thread::spawn(move || {
+            stdin
+                .write_all(input.as_bytes())
+                .and_then(|_| stdin.flush())
+        })
+    });
+
+    // After a timeout the writer is left to finish on its own, since processes
+    // the hook spawned may still hold its stdin open
+    let Some(output) = wait_with_timeout(child, timeout)? else {
+        return Ok(None);
+    };
+    if let Some(writer) = writer {
+        // Hooks are free to exit without reading their input
+        match writer.join() {
+            Ok(Err(e)) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(Some(output))
+}
+
+/// Output collected from a finished hook process
+struct HookOutput {
+    status: ExitStatus,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+/// Wait for the child to exit, killing it if `timeout` elapses first.
+///
+/// Returns `None` if the child was killed.
+fn wait_with_timeout(mut child: Child, timeout: Option<Duration>) -> Result<Option<HookOutput>> {
+    // Drain the pipes on separate threads so a chatty hook can't block on a full pipe
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    // Without a timeout, block so the exit is noticed immediately
+    let status = match timeout {
+        None => child.wait()?,
+        Some(timeout) => {
+            let deadline = Instant::now() + timeout;
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+                if Instant::now() >= deadline {
+                    child.kill()?;
+                    child.wait()?;
+                    return Ok(None);
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+    };
+
+    Ok(Some(HookOutput {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
 }
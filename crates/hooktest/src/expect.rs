@@ -0,0 +1,170 @@
+use crate::compat::legacy_to_new;
+use crate::execute::HookExecution;
+use serde_json::Value;
+use std::fmt;
+
+/// A decision the hook is expected to produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExpectedDecision {
+    /// Legacy `decision: approve` (or `permissionDecision: allow`)
+    Approve,
+    /// `permissionDecision: allow` (or legacy `decision: approve`)
+    Allow,
+    /// Legacy `decision: block` (or `permissionDecision: deny`)
+    Block,
+    /// `permissionDecision: deny` (or legacy `decision: block`)
+    Deny,
+    /// `permissionDecision: ask`
+    Ask,
+    /// No decision at all
+    None,
+}
+
+impl ExpectedDecision {
+    fn name(&self) -> &'static str {
+        match self {
+            ExpectedDecision::Approve => "approve",
+            ExpectedDecision::Allow => "allow",
+            ExpectedDecision::Block => "block",
+            ExpectedDecision::Deny => "deny",
+            ExpectedDecision::Ask => "ask",
+            ExpectedDecision::None => "none",
+        }
+    }
+
+    /// Whether `actual` satisfies this expectation, treating the legacy and
+    /// new spellings of the same decision as equal
+    fn matches(&self, actual: Option<&str>) -> bool {
+        legacy_to_new(self.name()) == legacy_to_new(actual.unwrap_or("none"))
+    }
+}
+
+impl fmt::Display for ExpectedDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Outcomes a hook run is expected to produce, from `--expect-*` flags
+#[derive(Debug, Default, Clone)]
+pub struct Expectations {
+    /// Expected decision
+    pub decision: Option<ExpectedDecision>,
+    /// Expected exit code
+    pub exit: Option<i32>,
+    /// JSON the parsed output must contain as a subset
+    pub json: Option<Value>,
+}
+
+impl Expectations {
+    /// Compare against a hook execution, describing each mismatch
+    pub fn check(&self, execution: &HookExecution) -> Vec<String> {
+        let mut mismatches = Vec::new();
+
+        if let Some(expected) = self.decision
+            && !expected.matches(execution.decision.as_deref())
+        {
+            mismatches.push(format!(
+                "expected decision={expected}, got {}",
+                execution.decision.as_deref().unwrap_or("none")
+            ));
+        }
+
+        if let Some(expected) = self.exit
+            && execution.exit_code != Some(expected)
+        {
+            let actual = execution
+                .exit_code
+                .map_or("none".to_string(), |code| code.to_string());
+            mismatches.push(format!("expected exit={expected}, got {actual}"));
+        }
+
+        if let Some(expected) = &self.json {
+            match &execution.parsed_output {
+                Some(actual) => mismatches.extend(subset_mismatch(expected, actual, "")),
+                None => mismatches.push("expected json output, got none".to_string()),
+            }
+        }
+
+        mismatches
+    }
+}
+
+/// Check that `actual` contains `expected`, returning a description of the
+/// first difference.
+///
+/// Objects match if every expected key is present with a matching value, so
+/// extra keys in `actual` are ignored. Arrays must have the same length and
+/// match element by element. Other values must be equal.
+fn subset_mismatch(expected: &Value, actual: &Value, path: &str) -> Option<String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.iter().find_map(|(key, expected)| {
+                let path = format!("{path}/{key}");
+                match actual.get(key) {
+                    Some(actual) => subset_mismatch(expected, actual, &path),
+                    None => Some(format!("expected json {path}={expected}, got nothing")),
+                }
+            })
+        }
+        (Value::Array(expected_items), Value::Array(actual_items))
+            if expected_items.len() == actual_items.len() =>
+        {
+            expected_items
+                .iter()
+                .zip(actual_items)
+                .enumerate()
+                .find_map(|(i, (expected, actual))| {
+                    subset_mismatch(expected, actual, &format!("{path}/{i}"))
+                })
+        }
+        _ if expected == actual => None,
+        _ => Some(format!(
+            "expected json {}={expected}, got {actual}",
+            if path.is_empty() { "/" } else { path }
+        )),
+    }
+}
+
+/// Parse the `--expect-json` argument
+pub fn parse_json(s: &str) -> Result<Value, String> {
+    serde_json::from_str(s).map_err(|e| format!("invalid JSON: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_subset_mismatch() {
+        let actual = json!({
+            "decision": "block",
+            "reason": "dangerous",
+            "metadata": {"ruleId": "git-001", "extra": true},
+            "tags": ["a", "b"]
+        });
+
+        assert_eq!(subset_mismatch(&json!({}), &actual, ""), None);
+        assert_eq!(
+            subset_mismatch(
+                &json!({"decision": "block", "metadata": {"ruleId": "git-001"}, "tags": ["a", "b"]}),
+                &actual,
+                ""
+            ),
+            None
+        );
+        assert_eq!(
+            subset_mismatch(&json!({"metadata": {"ruleId": "git-002"}}), &actual, ""),
+            Some(r#"expected json /metadata/ruleId="git-002", got "git-001""#.to_string())
+        );
+        assert_eq!(
+            subset_mismatch(&json!({"continue": false}), &actual, ""),
+            Some("expected json /continue=false, got nothing".to_string())
+        );
+        assert_eq!(
+            subset_mismatch(&json!({"tags": ["a"]}), &actual, ""),
+            Some(r#"expected json /tags=["a"], got ["a","b"]"#.to_string())
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use serde_json::Value;
+
+/// Output fields that carry feedback for Claude or the user, in the order
+/// they are collected from each output
+const FEEDBACK_FIELDS: [&str; 3] = ["reason", "stopReason", "systemMessage"];
+
+/// Collect the feedback a series of hook outputs would surface, in order.
+///
+/// For each output, the `reason`, `stopReason` and `systemMessage` string
+/// fields are taken in that order. Empty strings are skipped.
+pub fn collect_feedback(outputs: &[Value]) -> Vec<String> {
+    outputs
+        .iter()
+        .flat_map(|output| {
+            FEEDBACK_FIELDS
+                .iter()
+                .filter_map(move |field| output.get(field).and_then(|v| v.as_str()))
+        })
+        .filter(|feedback| !feedback.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_collect_feedback() {
+        let outputs = [
+            json!({"decision": "block", "reason": "Use rg instead of grep"}),
+            json!({"continue": false, "stopReason": "Build is broken", "systemMessage": "Run cargo check"}),
+            json!({"decision": "approve"}),
+        ];
+        assert_eq!(
+            collect_feedback(&outputs),
+            vec![
+                "Use rg instead of grep",
+                "Build is broken",
+                "Run cargo check"
+            ]
+        );
+    }
+}
@@ -1,6 +1,24 @@
 use anyhow::{Context, Result};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+
+/// Read a raw hook input payload from a file, or from stdin when the path is "-"
+pub fn read_input_file(path: &str) -> Result<String> {
+    let raw = if path == "-" {
+        let mut raw = String::new();
+        std::io::stdin()
+            .read_to_string(&mut raw)
+            .context("Failed to read hook input from stdin")?;
+        raw
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read input file {path}"))?
+    };
+    serde_json::from_str::<Value>(&raw)
+        .with_context(|| format!("Invalid JSON in hook input from {path}"))?;
+    Ok(raw)
+}
 
 /// Parse key=value pairs into a HashMap of string values
 pub fn parse_string_inputs(inputs: &[String]) -> Result<HashMap<String, Value>> {
@@ -1,81 +1,140 @@
 use crate::color::ColorMode;
-use anyhow::{Result, bail};
+use anyhow::Result;
 use code_hooks::{
-    HookResponse, Input, Notification, PostToolUse, PostToolUseOutput, PreToolUse,
-    PreToolUseOutput, Stop, SubagentStop, TranscriptReader,
+    EventKind, HookResponse, Input, Notification, PostToolUse, PostToolUseOutput, PreToolUse,
+    PreToolUseOutput, Stop, SubagentStop, TranscriptReader, UserPromptSubmit, redact_tool_input,
 };
+use regex::Regex;
 use serde::Serialize;
+use serde_json::Value;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Serialize)]
 struct LogEntry<'a, T> {
     event: String,
     timestamp: u64,
     data: &'a T,
+    /// Time taken to read the input and process it, in milliseconds
+    duration_ms: u64,
+    /// The response sent back to Claude Code
+    response: Value,
 }
 
 pub fn run_log_hook(
-    event: String,
+    event: EventKind,
     filepath: String,
     transcript_path: Option<String>,
+    redact: bool,
     _color_mode: ColorMode,
 ) -> Result<()> {
+    let start = Instant::now();
+    let patterns = if redact {
+        secret_patterns()
+    } else {
+        Vec::new()
+    };
     // Parse the input based on event type and handle it
-    match event.as_str() {
-        "pretool" => {
-            let input = PreToolUse::read()?;
-            log_event("pretool", &input, &filepath)?;
+    match event {
+        EventKind::PreToolUse => {
+            let mut input = PreToolUse::read()?;
             if let Some(transcript_path) = transcript_path {
                 process_transcript(&input, &transcript_path)?;
             }
-            PreToolUseOutput::passthrough().respond()
+            input.tool_input = redact_tool_input(&input.tool_input, &patterns);
+            let response = PreToolUseOutput::passthrough();
+            log_and_respond(event, &input, response, start, &filepath)
         }
-        "posttool" => {
-            let input = PostToolUse::read()?;
-            log_event("posttool", &input, &filepath)?;
+        EventKind::PostToolUse => {
+            let mut input = PostToolUse::read()?;
             if let Some(transcript_path) = transcript_path {
                 process_transcript(&input, &transcript_path)?;
             }
-            PostToolUseOutput::passthrough().respond()
+            input.tool_input = redact_tool_input(&input.tool_input, &patterns);
+            let response = PostToolUseOutput::passthrough();
+            log_and_respond(event, &input, response, start, &filepath)
         }
-        "notification" => {
+        EventKind::Notification => {
             let input = Notification::read()?;
-            log_event("notification", &input, &filepath)?;
             if let Some(transcript_path) = transcript_path {
                 process_transcript(&input, &transcript_path)?;
             }
-            Notification::passthrough().respond()
+            let response = Notification::passthrough();
+            log_and_respond(event, &input, response, start, &filepath)
         }
-        "stop" => {
+        EventKind::Stop => {
             let input = Stop::read()?;
-            log_event("stop", &input, &filepath)?;
             if let Some(transcript_path) = transcript_path {
                 process_transcript(&input, &transcript_path)?;
             }
-            input.allow().respond()
+            let response = input.allow();
+            log_and_respond(event, &input, response, start, &filepath)
         }
-        "subagentstop" => {
+        EventKind::SubagentStop => {
             let input = SubagentStop::read()?;
-            log_event("subagentstop", &input, &filepath)?;
             if let Some(transcript_path) = transcript_path {
                 process_transcript(&input, &transcript_path)?;
             }
-            input.allow().respond()
+            let response = input.allow();
+            log_and_respond(event, &input, response, start, &filepath)
+        }
+        EventKind::UserPromptSubmit => {
+            let input = UserPromptSubmit::read()?;
+            if let Some(transcript_path) = transcript_path {
+                process_transcript(&input, &transcript_path)?;
+            }
+            let response = input.passthrough();
+            log_and_respond(event, &input, response, start, &filepath)
         }
-        _ => bail!(
-            "Unknown event type: {}. Must be one of: pretool, posttool, notification, stop, subagentstop",
-            event
-        ),
     }
 }
 
-fn log_event<T: serde::Serialize>(event_name: &str, data: &T, filepath: &str) -> Result<()> {
+/// Patterns for secrets commonly passed to commands, such as `--token=...`,
+/// `--token ...`, `API_KEY=...`, headers like `X-Api-Key: ...` and
+/// `Authorization: ...`, or a bearer token
+fn secret_patterns() -> Vec<Regex> {
+    [
+        // The whole header value, which may be several words
+        r#"(?i)\bauthorization\s*:\s*[^"'\n]+"#,
+        r#"(?i)\b[\w-]*(token|key|secret|password)[\w-]*\s*:\s*[^\s"']+"#,
+        // A flag followed by its value, quoted or not
+        r#"(?i)\B--?[\w-]*(token|key|secret|password)[\w-]*\s+("[^"]*"|'[^']*'|\S+)"#,
+        r"(?i)-*\b\w*(token|key|secret|password)\w*=\S+",
+        r"(?i)\bbearer\s+\S+",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("valid secret pattern"))
+    .collect()
+}
+
+/// Log the event with its response and processing time, then send the response
+fn log_and_respond<T: Serialize, R: HookResponse>(
+    event: EventKind,
+    data: &T,
+    response: R,
+    start: Instant,
+    filepath: &str,
+) -> Result<()> {
+    let response = response.try_respond()?;
+    log_event(event, data, &response, start.elapsed(), filepath)?;
+    println!("{response}");
+    Ok(())
+}
+
+fn log_event<T: Serialize>(
+    event: EventKind,
+    data: &T,
+    response: &str,
+    duration: Duration,
+    filepath: &str,
+) -> Result<()> {
     let log_entry = LogEntry {
-        event: event_name.to_string(),
+        event: event.to_string(),
         timestamp: get_timestamp(),
         data,
+        duration_ms: duration.as_millis() as u64,
+        response: serde_json::from_str(response)?,
     };
     let mut file = OpenOptions::new()
         .create(true)
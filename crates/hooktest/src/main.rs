@@ -1,18 +1,34 @@
 mod color;
+mod compat;
+mod diff;
 mod execute;
+mod expect;
+mod feedback;
 mod input;
 mod log;
 mod notification;
 mod output;
 mod posttool;
 mod pretool;
+mod render_spec;
+mod replay;
+mod scaffold;
 mod stop;
 mod subagent_stop;
+mod tee;
+mod transcript;
+mod user_prompt_submit;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use code_hooks::EventKind;
 use color::ColorMode;
-use std::time::{SystemTime, UNIX_EPOCH};
+use execute::{ExecOptions, OutputFormat};
+use expect::{Expectations, ExpectedDecision};
+use render_spec::RenderSpec;
+use scaffold::Dependency;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Parser)]
 #[command(
@@ -21,7 +37,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
     version
 )]
 struct Cli {
-    /// Enable colored output
+    /// Enable colored output. Without --color or --no-color, color follows
+    /// NO_COLOR, CLICOLOR_FORCE and whether stdout is a terminal.
     #[arg(long, global = true, conflicts_with = "no_color")]
     color: bool,
 
@@ -29,12 +46,83 @@ struct Cli {
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// Kill the hook if it runs longer than this many seconds
+    #[arg(long, global = true, value_name = "SECONDS")]
+    timeout: Option<f64>,
+
+    /// Output format: a human-readable report, or a single JSON object for CI
+    #[arg(long, global = true, value_enum, default_value = "human")]
+    format: OutputFormat,
+
+    /// Exit with status 1 unless the hook produces this decision
+    #[arg(long, global = true, value_enum, value_name = "DECISION")]
+    expect_decision: Option<ExpectedDecision>,
+
+    /// Exit with status 1 unless the hook exits with this code
+    #[arg(long, global = true, value_name = "CODE")]
+    expect_exit: Option<i32>,
+
+    /// Exit with status 1 unless the hook's output contains this JSON as a subset
+    #[arg(long, global = true, value_name = "JSON", value_parser = expect::parse_json)]
+    expect_json: Option<serde_json::Value>,
+
+    /// JSON file mapping custom hook output fields to descriptions, used to explain them
+    #[arg(long, global = true, value_name = "PATH")]
+    render_spec: Option<String>,
+
+    /// Print the input JSON sent to the hook on a single line, e.g. for
+    /// copying into a fixture
+    #[arg(long, global = true)]
+    compact_input: bool,
+
+    /// Pass the hook's stderr straight through to the terminal as it's
+    /// written, instead of printing it after the hook exits
+    #[arg(long, global = true)]
+    stream: bool,
+
+    /// Don't print the input sent to the hook, e.g. when it contains secrets
+    #[arg(long, global = true)]
+    no_input_echo: bool,
+
+    /// Run the hook N times and report min/median/max/mean durations
+    /// instead of its output
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: Option<u32>,
+
+    /// Set an environment variable for the hook (repeatable). CLAUDE_PROJECT_DIR
+    /// defaults to the current directory.
+    #[arg(long = "env", global = true, value_name = "KEY=VALUE", value_parser = execute::parse_env)]
+    env: Vec<(String, String)>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Test a UserPromptSubmit hook
+    #[command(name = "userpromptsubmit")]
+    UserPromptSubmit {
+        /// Session ID for the hook (generated if not provided)
+        #[arg(long)]
+        sessionid: Option<String>,
+
+        /// Transcript path for the hook
+        #[arg(long, default_value = "/tmp/transcript.json")]
+        transcript: String,
+
+        /// The prompt the user submitted
+        #[arg(long, default_value = "Write a function that adds two numbers")]
+        prompt: String,
+
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
+        /// Hook command and arguments (everything after --)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        hook_args: Vec<String>,
+    },
     /// Test a PreToolUse hook
     #[command(name = "pretool")]
     PreTool {
@@ -46,9 +134,9 @@ enum Commands {
         #[arg(long, default_value = "/tmp/transcript.json")]
         transcript: String,
 
-        /// Tool name being called
-        #[arg(long, default_value = "Bash")]
-        tool: String,
+        /// Tool name being called (default: Bash)
+        #[arg(long)]
+        tool: Option<String>,
 
         /// Tool input as key=value pairs (e.g., --tool-input command="echo hello")
         #[arg(long = "tool-input", value_name = "KEY=VALUE")]
@@ -58,6 +146,14 @@ enum Commands {
         #[arg(long = "tool-input-json", value_name = "KEY=JSON")]
         tool_input_json: Vec<String>,
 
+        /// Show Edit and MultiEdit old_string/new_string as a diff instead of raw JSON
+        #[arg(long)]
+        show_diff: bool,
+
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
         /// Hook command and arguments (everything after --)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         hook_args: Vec<String>,
@@ -73,9 +169,9 @@ enum Commands {
         #[arg(long, default_value = "/tmp/transcript.json")]
         transcript: String,
 
-        /// Tool name that was called
-        #[arg(long, default_value = "Bash")]
-        tool: String,
+        /// Tool name that was called (default: Bash)
+        #[arg(long)]
+        tool: Option<String>,
 
         /// Tool input as key=value pairs (e.g., --tool-input command="echo hello")
         #[arg(long = "tool-input", value_name = "KEY=VALUE")]
@@ -93,6 +189,10 @@ enum Commands {
         #[arg(long = "tool-response-json", value_name = "KEY=JSON")]
         tool_response_json: Vec<String>,
 
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
         /// Hook command and arguments (everything after --)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         hook_args: Vec<String>,
@@ -116,6 +216,10 @@ enum Commands {
         #[arg(long, default_value = "Claude Code")]
         title: String,
 
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
         /// Hook command and arguments (everything after --)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         hook_args: Vec<String>,
@@ -135,6 +239,10 @@ enum Commands {
         #[arg(long, default_value = "false")]
         active: bool,
 
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
         /// Hook command and arguments (everything after --)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         hook_args: Vec<String>,
@@ -154,15 +262,133 @@ enum Commands {
         #[arg(long, default_value = "false")]
         active: bool,
 
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
+        /// Hook command and arguments (everything after --)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        hook_args: Vec<String>,
+    },
+    /// Check a PreToolUse hook's output works with both legacy and new protocols
+    #[command(name = "compat")]
+    Compat {
+        /// Tool name being called (default: Bash)
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Tool input as key=value pairs (e.g., --tool-input command="echo hello")
+        #[arg(long = "tool-input", value_name = "KEY=VALUE")]
+        tool_input: Vec<String>,
+
+        /// Tool input as key=json pairs (e.g., --tool-input-json args='["one", "two"]')
+        #[arg(long = "tool-input-json", value_name = "KEY=JSON")]
+        tool_input_json: Vec<String>,
+
+        /// Read the raw hook input JSON from a file ("-" for stdin), bypassing input flags
+        #[arg(long = "input-file", visible_alias = "input", value_name = "PATH")]
+        input_file: Option<String>,
+
+        /// Hook command and arguments (everything after --)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        hook_args: Vec<String>,
+    },
+    /// Forward JSON payloads written to a named pipe to a hook
+    #[command(name = "tee")]
+    Tee {
+        /// Path to the named pipe to read payloads from
+        #[arg(long)]
+        fifo: String,
+
+        /// Exit after forwarding this many payloads (runs until interrupted if not provided)
+        #[arg(long)]
+        count: Option<usize>,
+
+        /// Hook command and arguments (everything after --)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        hook_args: Vec<String>,
+    },
+    /// Replay events recorded by `log` through a hook, reporting pass/fail per entry
+    #[command(name = "replay")]
+    Replay {
+        /// JSONL log file written by the log subcommand
+        logfile: String,
+
         /// Hook command and arguments (everything after --)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         hook_args: Vec<String>,
     },
+    /// Display transcript files, or print a de-identified copy for sharing
+    #[command(name = "transcript")]
+    Transcript {
+        /// Transcript JSONL files
+        #[arg(required = true)]
+        paths: Vec<String>,
+
+        /// Print the transcript as JSONL with the home directory and session ids scrubbed
+        #[arg(long)]
+        anonymize: bool,
+
+        /// With --anonymize, also replace tool results and outputs with a placeholder
+        #[arg(long, requires = "anonymize")]
+        strip_tool_outputs: bool,
+
+        /// Print how often each tool is used across the transcripts
+        #[arg(long, conflicts_with = "anonymize")]
+        tool_summary: bool,
+
+        /// Check entries have the fields this Claude Code version writes (e.g. 1.0.43)
+        #[arg(long, value_name = "VERSION", conflicts_with_all = ["anonymize", "tool_summary"])]
+        schema_version: Option<semver::Version>,
+
+        /// Only check that every line parses, failing if any don't. Files are
+        /// streamed, so this works on very large transcripts.
+        #[arg(long, conflicts_with_all = ["anonymize", "tool_summary", "schema_version"])]
+        strict: bool,
+
+        /// Only display entries of these types (comma-separated: user, assistant, system, summary)
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            value_name = "TYPES",
+            conflicts_with_all = ["anonymize", "tool_summary", "schema_version", "strict"]
+        )]
+        only: Vec<transcript::EntryType>,
+
+        /// Print counts of each entry type, tool uses, tokens and parse errors
+        /// instead of the entries
+        #[arg(
+            long,
+            conflicts_with_all = ["anonymize", "tool_summary", "schema_version", "strict", "only"]
+        )]
+        stats: bool,
+    },
+    /// Create a Cargo project for a new hook built on code-hooks
+    #[command(name = "new")]
+    New {
+        /// Name of the hook package, also used as its directory
+        name: String,
+
+        /// Event the hook handles (userpromptsubmit, pretool, posttool, notification, stop, subagentstop)
+        #[arg(long)]
+        event: EventKind,
+
+        /// Depend on a local checkout of the code-hooks crate instead of the
+        /// git repository
+        #[arg(long, value_name = "DIR", conflicts_with = "git")]
+        path: Option<std::path::PathBuf>,
+
+        /// Git repository to depend on code-hooks from [default: the
+        /// code-hooks repository]
+        #[arg(long, value_name = "URL")]
+        git: Option<String>,
+    },
     /// Read an event from stdin and appendit to a JSONL log file
     #[command(name = "log")]
     Log {
-        /// Event type to log (pretool, posttool, notification, stop, subagentstop)
-        event: String,
+        /// Event type to log (userpromptsubmit, pretool, posttool, notification, stop, subagentstop)
+        event: EventKind,
 
         /// File path to write the log
         filepath: String,
@@ -170,6 +396,10 @@ enum Commands {
         /// Optional path to read and rewrite transcript
         #[arg(long)]
         transcript: Option<String>,
+
+        /// Replace secrets in tool inputs, such as `--token=...`, with `***` in the log
+        #[arg(long)]
+        redact: bool,
     },
 }
 
@@ -182,45 +412,157 @@ fn generate_session_id() -> String {
     format!("test-session-{timestamp}")
 }
 
+/// Build the tool input from flags, defaulting to a harmless command for Bash
+fn tool_input_map(
+    tool: &str,
+    tool_input: &[String],
+    tool_input_json: &[String],
+) -> Result<HashMap<String, serde_json::Value>> {
+    if tool_input.is_empty() && tool_input_json.is_empty() {
+        // No inputs provided, use default based on tool
+        let mut default_map = HashMap::new();
+        if tool == "Bash" {
+            default_map.insert(
+                "command".to_string(),
+                serde_json::Value::String("echo 'test'".to_string()),
+            );
+        }
+        Ok(default_map)
+    } else {
+        // Combine tool-input and tool-input-json
+        input::combine_inputs(None, tool_input, tool_input_json)
+    }
+}
+
+/// Read the raw hook input if `--input-file` was given, warning about any
+/// flags that are ignored as a result
+fn load_raw_input(
+    input_file: Option<String>,
+    ignored_flags: &[(&str, bool)],
+) -> Result<Option<String>> {
+    let Some(path) = input_file else {
+        return Ok(None);
+    };
+    for (flag, set) in ignored_flags {
+        if *set {
+            eprintln!("Warning: {flag} is ignored when --input-file is given");
+        }
+    }
+    input::read_input_file(&path).map(Some)
+}
+
+/// Options that apply to every hook execution, from the global flags
+struct GlobalOptions {
+    timeout: Option<Duration>,
+    format: OutputFormat,
+    expect: Expectations,
+    render_spec: Option<RenderSpec>,
+    env: Vec<(String, String)>,
+    compact_input: bool,
+    stream: bool,
+    no_input_echo: bool,
+    repeat: Option<u32>,
+}
+
+/// Bundle the hook command with the optional captured input and global options
+fn exec_options(
+    hook_args: Vec<String>,
+    input_file: Option<String>,
+    ignored_flags: &[(&str, bool)],
+    globals: &GlobalOptions,
+) -> Result<ExecOptions> {
+    Ok(ExecOptions {
+        hook_args,
+        raw_input: load_raw_input(input_file, ignored_flags)?,
+        timeout: globals.timeout,
+        format: globals.format,
+        expect: globals.expect.clone(),
+        render_spec: globals.render_spec.clone(),
+        env: globals.env.clone(),
+        compact_input: globals.compact_input,
+        stream: globals.stream,
+        no_input_echo: globals.no_input_echo,
+        repeat: globals.repeat,
+    })
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
     let color_mode = ColorMode::from_flags(cli.color, cli.no_color);
+    let globals = GlobalOptions {
+        timeout: cli
+            .timeout
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .context("Invalid --timeout")?,
+        format: cli.format,
+        expect: Expectations {
+            decision: cli.expect_decision,
+            exit: cli.expect_exit,
+            json: cli.expect_json,
+        },
+        render_spec: cli
+            .render_spec
+            .as_deref()
+            .map(RenderSpec::load)
+            .transpose()?,
+        env: cli.env,
+        compact_input: cli.compact_input,
+        stream: cli.stream,
+        no_input_echo: cli.no_input_echo,
+        repeat: cli.repeat,
+    };
 
     match cli.command {
+        Commands::UserPromptSubmit {
+            sessionid,
+            transcript,
+            prompt,
+            input_file,
+            hook_args,
+        } => {
+            let session_id = sessionid.unwrap_or_else(generate_session_id);
+            let opts = exec_options(hook_args, input_file, &[], &globals)?;
+            let execution = user_prompt_submit::run_user_prompt_submit_hook(
+                session_id, transcript, prompt, &opts, color_mode,
+            )?;
+            opts.finish(&execution)
+        }
         Commands::PreTool {
             sessionid,
             transcript,
             tool,
             tool_input,
             tool_input_json,
+            show_diff,
+            input_file,
             hook_args,
         } => {
             let session_id = sessionid.unwrap_or_else(generate_session_id);
-
-            // Handle tool input
-            let tool_input_map = if tool_input.is_empty() && tool_input_json.is_empty() {
-                // No inputs provided, use default based on tool
-                let mut default_map = std::collections::HashMap::new();
-                if tool == "Bash" {
-                    default_map.insert(
-                        "command".to_string(),
-                        serde_json::Value::String("echo 'test'".to_string()),
-                    );
-                }
-                default_map
-            } else {
-                // Combine tool-input and tool-input-json
-                input::combine_inputs(None, &tool_input, &tool_input_json)?
-            };
-
-            pretool::run_pretooluse_hook(
+            let opts = exec_options(
+                hook_args,
+                input_file,
+                &[
+                    ("--tool", tool.is_some()),
+                    ("--tool-input", !tool_input.is_empty()),
+                    ("--tool-input-json", !tool_input_json.is_empty()),
+                ],
+                &globals,
+            )?;
+            let tool = tool.unwrap_or_else(|| "Bash".to_string());
+
+            let tool_input_map = tool_input_map(&tool, &tool_input, &tool_input_json)?;
+
+            let execution = pretool::run_pretooluse_hook(
                 session_id,
                 transcript,
                 tool,
                 tool_input_map,
-                hook_args,
+                show_diff,
+                &opts,
                 color_mode,
-            )
+            )?;
+            opts.finish(&execution)
         }
         Commands::PostTool {
             sessionid,
@@ -230,25 +572,25 @@ fn main() -> Result<()> {
             tool_input_json,
             tool_response,
             tool_response_json,
+            input_file,
             hook_args,
         } => {
             let session_id = sessionid.unwrap_or_else(generate_session_id);
-
-            // Handle tool input
-            let tool_input_map = if tool_input.is_empty() && tool_input_json.is_empty() {
-                // No inputs provided, use default based on tool
-                let mut default_map = std::collections::HashMap::new();
-                if tool == "Bash" {
-                    default_map.insert(
-                        "command".to_string(),
-                        serde_json::Value::String("echo 'test'".to_string()),
-                    );
-                }
-                default_map
-            } else {
-                // Combine tool-input and tool-input-json
-                input::combine_inputs(None, &tool_input, &tool_input_json)?
-            };
+            let opts = exec_options(
+                hook_args,
+                input_file,
+                &[
+                    ("--tool", tool.is_some()),
+                    ("--tool-input", !tool_input.is_empty()),
+                    ("--tool-input-json", !tool_input_json.is_empty()),
+                    ("--tool-response", !tool_response.is_empty()),
+                    ("--tool-response-json", !tool_response_json.is_empty()),
+                ],
+                &globals,
+            )?;
+            let tool = tool.unwrap_or_else(|| "Bash".to_string());
+
+            let tool_input_map = tool_input_map(&tool, &tool_input, &tool_input_json)?;
 
             // Handle tool response
             let tool_response_map = if tool_response.is_empty() && tool_response_json.is_empty() {
@@ -264,52 +606,137 @@ fn main() -> Result<()> {
                 input::combine_inputs(None, &tool_response, &tool_response_json)?
             };
 
-            posttool::run_posttooluse_hook(
+            let execution = posttool::run_posttooluse_hook(
                 session_id,
                 transcript,
                 tool,
                 tool_input_map,
                 tool_response_map,
-                hook_args,
+                &opts,
                 color_mode,
-            )
+            )?;
+            opts.finish(&execution)
         }
         Commands::Notification {
             sessionid,
             transcript,
             message,
             title,
+            input_file,
             hook_args,
         } => {
             let session_id = sessionid.unwrap_or_else(generate_session_id);
-            notification::run_notification_hook(
-                session_id, transcript, message, title, hook_args, color_mode,
-            )
+            let opts = exec_options(hook_args, input_file, &[], &globals)?;
+            let execution = notification::run_notification_hook(
+                session_id, transcript, message, title, &opts, color_mode,
+            )?;
+            opts.finish(&execution)
         }
         Commands::Stop {
             sessionid,
             transcript,
             active,
+            input_file,
             hook_args,
         } => {
             let session_id = sessionid.unwrap_or_else(generate_session_id);
-            stop::run_stop_hook(session_id, transcript, active, hook_args, color_mode)
+            let opts = exec_options(hook_args, input_file, &[], &globals)?;
+            let execution = stop::run_stop_hook(session_id, transcript, active, &opts, color_mode)?;
+            opts.finish(&execution)
         }
         Commands::SubagentStop {
             sessionid,
             transcript,
             active,
+            input_file,
             hook_args,
         } => {
             let session_id = sessionid.unwrap_or_else(generate_session_id);
-            subagent_stop::run_subagent_stop_hook(
-                session_id, transcript, active, hook_args, color_mode,
-            )
+            let opts = exec_options(hook_args, input_file, &[], &globals)?;
+            let execution = subagent_stop::run_subagent_stop_hook(
+                session_id, transcript, active, &opts, color_mode,
+            )?;
+            opts.finish(&execution)
         }
+        Commands::Compat {
+            tool,
+            tool_input,
+            tool_input_json,
+            input_file,
+            hook_args,
+        } => {
+            let opts = exec_options(
+                hook_args,
+                input_file,
+                &[
+                    ("--tool", tool.is_some()),
+                    ("--tool-input", !tool_input.is_empty()),
+                    ("--tool-input-json", !tool_input_json.is_empty()),
+                ],
+                &globals,
+            )?;
+            let tool = tool.unwrap_or_else(|| "Bash".to_string());
+            let tool_input_map = tool_input_map(&tool, &tool_input, &tool_input_json)?;
+            let execution = compat::run_compat(tool, tool_input_map, &opts, color_mode)?;
+            opts.finish(&execution)
+        }
+        Commands::Tee {
+            fifo,
+            count,
+            hook_args,
+        } => {
+            let opts = exec_options(hook_args, None, &[], &globals)?;
+            tee::run_tee(fifo, count, opts, color_mode)
+        }
+        Commands::Replay { logfile, hook_args } => {
+            let opts = exec_options(hook_args, None, &[], &globals)?;
+            replay::run_replay(logfile, opts, color_mode)
+        }
+        Commands::Transcript {
+            paths,
+            anonymize,
+            strip_tool_outputs,
+            tool_summary,
+            schema_version,
+            strict,
+            only,
+            stats,
+        } => transcript::run_transcript(
+            paths,
+            transcript::TranscriptOptions {
+                anonymize,
+                strip_tool_outputs,
+                tool_summary,
+                schema_version,
+                strict,
+                only,
+                stats,
+            },
+            color_mode,
+        ),
         Commands::Log {
             event,
             filepath,
             transcript,
-        } => log::run_log_hook(event, filepath, transcript, color_mode),
+            redact,
+        } => log::run_log_hook(event, filepath, transcript, redact, color_mode),
+        Commands::New {
+            name,
+            event,
+            path,
+            git,
+        } => {
+            let dependency = match (path, git) {
+                (Some(path), _) => Dependency::Path(
+                    std::fs::canonicalize(&path)
+                        .with_context(|| format!("Failed to resolve {}", path.display()))?,
+                ),
+                (None, Some(url)) => Dependency::Git(url),
+                (None, None) => Dependency::default(),
+            };
+            scaffold::write_scaffold(std::path::Path::new(&name), &name, event, &dependency)?;
+            println!("Created {event} hook in {name}/");
+            Ok(())
+        }
     }
 }
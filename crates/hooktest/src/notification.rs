@@ -1,37 +1,34 @@
 use crate::color::ColorMode;
-use crate::execute::execute_hook;
-use crate::output::Output;
+use crate::execute::{ExecOptions, HookExecution, execute_hook};
 use anyhow::Result;
-use code_hooks::Notification;
+use code_hooks::{EventKind, Notification};
 
 pub fn run_notification_hook(
     session_id: String,
     transcript_path: String,
     message: String,
     title: String,
-    hook_args: Vec<String>,
+    opts: &ExecOptions,
     color_mode: ColorMode,
-) -> Result<()> {
-    let mut out = Output::new(color_mode);
+) -> Result<HookExecution> {
+    let mut out = opts.output(color_mode);
 
     // Create the hook input using the Notification struct
     let hook_input = Notification {
         session_id,
         transcript_path,
+        hook_event_name: Some(EventKind::Notification.hook_event_name().to_string()),
         message,
-        hook_event_name: title,
+        title: Some(title),
     };
 
-    // Serialize to JSON
-    let input_json = serde_json::to_string(&hook_input)?;
+    // Serialize to JSON, unless a captured payload was provided
+    let input_json = opts.input_json(&hook_input)?;
+    let input_value: serde_json::Value = serde_json::from_str(&input_json)?;
 
     // Execute the hook and parse output
-    if let Some(hook_output) = execute_hook(
-        &mut out,
-        &hook_args,
-        &input_json,
-        &serde_json::to_value(&hook_input)?,
-    )? {
+    let execution = execute_hook(&mut out, opts, &input_json, &input_value)?;
+    if let Some(hook_output) = &execution.parsed_output {
         out.h1("What Claude/User Would See")?;
 
         // Check continue field
@@ -49,7 +46,9 @@ pub fn run_notification_hook(
             out.newline()?;
             out.dimmed("Output would be hidden in transcript mode")?;
         }
+
+        opts.annotate_output(&mut out, hook_output)?;
     }
 
-    Ok(())
+    Ok(execution)
 }
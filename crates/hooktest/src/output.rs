@@ -1,11 +1,11 @@
 use crate::color::{ColorMode, JsonHighlighter};
 use anyhow::Result;
-use std::io::Write;
-use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use std::io::{self, Write};
+use termcolor::{Color, ColorChoice, ColorSpec, NoColor, StandardStream, WriteColor};
 
 pub struct Output {
-    stdout: StandardStream,
-    json_highlighter: JsonHighlighter,
+    stdout: Box<dyn WriteColor>,
+    json_highlighter: Option<JsonHighlighter>,
 }
 
 impl Output {
@@ -16,8 +16,17 @@ impl Output {
             ColorChoice::Never
         };
         Self {
-            stdout: StandardStream::stdout(color_choice),
-            json_highlighter: JsonHighlighter::new(color_mode),
+            stdout: Box::new(StandardStream::stdout(color_choice)),
+            json_highlighter: Some(JsonHighlighter::new(color_mode)),
+        }
+    }
+
+    /// An output that discards everything, for when stdout is reserved for
+    /// machine-readable results
+    pub fn quiet() -> Self {
+        Self {
+            stdout: Box::new(NoColor::new(io::sink())),
+            json_highlighter: None,
         }
     }
 
@@ -79,8 +88,19 @@ impl Output {
 
     /// Print JSON with syntax highlighting
     pub fn json(&mut self, json: &serde_json::Value) -> Result<()> {
-        let json_str = serde_json::to_string_pretty(json)?;
-        self.json_highlighter.print_json(&json_str)?;
+        if let Some(json_highlighter) = &self.json_highlighter {
+            let json_str = serde_json::to_string_pretty(json)?;
+            json_highlighter.print_json(&json_str)?;
+        }
+        Ok(())
+    }
+
+    /// Print JSON on a single line with syntax highlighting
+    pub fn json_compact(&mut self, json: &serde_json::Value) -> Result<()> {
+        if let Some(json_highlighter) = &self.json_highlighter {
+            let json_str = serde_json::to_string(json)?;
+            json_highlighter.print_json(&json_str)?;
+        }
         Ok(())
     }
 
@@ -100,7 +120,6 @@ impl Output {
     }
 
     /// Write line directly to stdout
-    #[allow(dead_code)]
     pub fn writeln(&mut self, text: &str) -> Result<()> {
         writeln!(self.stdout, "{text}")?;
         Ok(())
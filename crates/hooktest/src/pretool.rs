@@ -1,42 +1,93 @@
 use crate::color::ColorMode;
-use crate::execute::execute_hook;
-use crate::output::Output;
+use crate::diff::render_edit_diff;
+use crate::execute::{ExecOptions, HookExecution, execute_hook_with_input_view};
 use anyhow::Result;
-use code_hooks::PreToolUse;
+use code_hooks::{EventKind, PreToolUse};
 use std::collections::HashMap;
+use termcolor::Color;
 
 pub fn run_pretooluse_hook(
     session_id: String,
     transcript_path: String,
     tool_name: String,
     tool_input: HashMap<String, serde_json::Value>,
-    hook_args: Vec<String>,
+    show_diff: bool,
+    opts: &ExecOptions,
     color_mode: ColorMode,
-) -> Result<()> {
-    let mut out = Output::new(color_mode);
+) -> Result<HookExecution> {
+    let mut out = opts.output(color_mode);
 
     // Create the hook input using the PreToolUse struct
     let hook_input = PreToolUse {
         session_id,
         transcript_path,
+        hook_event_name: Some(EventKind::PreToolUse.hook_event_name().to_string()),
         tool_name,
         tool_input,
+        permission_mode: None,
     };
 
-    // Serialize to JSON
-    let input_json = serde_json::to_string(&hook_input)?;
+    // Serialize to JSON, unless a captured payload was provided
+    let input_json = opts.input_json(&hook_input)?;
+    let input_value: serde_json::Value = serde_json::from_str(&input_json)?;
 
     // Execute the hook and parse output
-    if let Some(hook_output) = execute_hook(
-        &mut out,
-        &hook_args,
-        &input_json,
-        &serde_json::to_value(&hook_input)?,
-    )? {
+    // With --show-diff, Edit and MultiEdit inputs are shown as a diff
+    let execution =
+        execute_hook_with_input_view(&mut out, opts, &input_json, &input_value, |out| {
+            if show_diff && render_edit_diff(out, &input_value)? {
+                return Ok(());
+            }
+            opts.show_input(out, &input_value)
+        })?;
+    if let Some(hook_output) = &execution.parsed_output {
         out.h1("What Claude/User Would See")?;
 
-        // Parse decision field
-        if let Some(decision) = hook_output.get("decision").and_then(|d| d.as_str()) {
+        let specific = hook_output.get("hookSpecificOutput");
+        let permission_decision = specific
+            .and_then(|s| s.get("permissionDecision"))
+            .and_then(|d| d.as_str());
+
+        // Parse the newer permissionDecision field first, falling back to the legacy decision
+        if let Some(decision) = permission_decision {
+            let reason = specific
+                .and_then(|s| s.get("permissionDecisionReason"))
+                .and_then(|r| r.as_str());
+            match decision {
+                "allow" => {
+                    out.write("Permission Decision: ")?;
+                    out.success("ALLOW")?;
+                    out.newline()?;
+
+                    if let Some(reason) = reason {
+                        out.label("User sees", reason)?;
+                        out.dimmed("Claude sees: (nothing, tool proceeds)")?;
+                    }
+                }
+                "deny" => {
+                    out.write("Permission Decision: ")?;
+                    out.error("DENY")?;
+                    out.newline()?;
+
+                    if let Some(reason) = reason {
+                        out.label("User sees", "Tool blocked by hook")?;
+                        out.label("Claude sees", reason)?;
+                    }
+                }
+                "ask" => {
+                    out.write("Permission Decision: ")?;
+                    out.color("ASK", Color::Yellow, true)?;
+                    out.newline()?;
+
+                    if let Some(reason) = reason {
+                        out.label("User is asked to confirm", reason)?;
+                    }
+                }
+                _ => {
+                    out.label("Permission Decision", &format!("Unknown ({decision})"))?;
+                }
+            }
+        } else if let Some(decision) = hook_output.get("decision").and_then(|d| d.as_str()) {
             match decision {
                 "approve" => {
                     out.write("Decision: ")?;
@@ -66,6 +117,13 @@ pub fn run_pretooluse_hook(
             out.dimmed("Decision: NONE (follows normal permission flow)")?;
         }
 
+        if let Some(rule_id) = hook_output
+            .pointer("/metadata/ruleId")
+            .and_then(|r| r.as_str())
+        {
+            out.label("Rule", rule_id)?;
+        }
+
         if hook_output.get("continue").and_then(|c| c.as_bool()) == Some(false) {
             out.newline()?;
             out.error("Claude would STOP processing")?;
@@ -74,7 +132,9 @@ pub fn run_pretooluse_hook(
                 out.label("Stop reason shown to user", reason)?;
             }
         }
+
+        opts.annotate_output(&mut out, hook_output)?;
     }
 
-    Ok(())
+    Ok(execution)
 }
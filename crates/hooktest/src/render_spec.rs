@@ -0,0 +1,61 @@
+use crate::output::Output;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Top-level output fields Claude Code understands, which hooktest explains itself
+const KNOWN_FIELDS: &[&str] = &[
+    "decision",
+    "reason",
+    "continue",
+    "stopReason",
+    "suppressOutput",
+    "systemMessage",
+    "hookSpecificOutput",
+    "metadata",
+];
+
+/// Descriptions of custom output fields, loaded from a `--render-spec` file.
+///
+/// The file is a JSON object mapping output keys to human descriptions, e.g.
+/// `{"severity": "How serious the violation is"}`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct RenderSpec {
+    fields: BTreeMap<String, String>,
+}
+
+impl RenderSpec {
+    /// Load a spec from a JSON file
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read render spec {path}"))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid render spec {path}"))
+    }
+
+    /// Explain the fields of `hook_output` that Claude Code doesn't recognize,
+    /// using the spec's descriptions
+    pub fn annotate(&self, out: &mut Output, hook_output: &Value) -> Result<()> {
+        let Some(fields) = hook_output.as_object() else {
+            return Ok(());
+        };
+        let unknown: Vec<(&String, &Value)> = fields
+            .iter()
+            .filter(|(key, _)| !KNOWN_FIELDS.contains(&key.as_str()))
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+
+        out.newline()?;
+        for (key, value) in unknown {
+            match self.fields.get(key) {
+                Some(description) => out.label(key, &format!("{description} ({value})"))?,
+                None => out.dimmed(&format!("{key}: not described by the render spec"))?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use crate::color::ColorMode;
+use crate::execute::{ExecOptions, HookExecution, OutputFormat, execute_hook};
+use crate::feedback::collect_feedback;
+use anyhow::{Context, Result};
+use code_hooks::{
+    EventKind, Notification, PostToolUse, PreToolUse, Stop, SubagentStop, UserPromptSubmit,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use termcolor::Color;
+
+/// A record written by the `log` subcommand
+#[derive(Deserialize)]
+struct LogRecord {
+    event: String,
+    data: Value,
+}
+
+/// Replay each event recorded by `hooktest log` through a hook.
+///
+/// An entry fails if the hook exits with a code other than 0 or 2 (the codes
+/// Claude Code treats as deliberate), times out, or misses a `--expect-*`
+/// expectation. With `--format json`, one JSON object is printed per entry.
+pub fn run_replay(logfile: String, opts: ExecOptions, color_mode: ColorMode) -> Result<()> {
+    let content =
+        fs::read_to_string(&logfile).with_context(|| format!("Failed to read log {logfile}"))?;
+    let mut out = opts.output(color_mode);
+    let mut failed = 0;
+    let mut total = 0;
+    let mut outputs = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        let record: LogRecord = serde_json::from_str(line)
+            .with_context(|| format!("Invalid log record at {logfile}:{line_number}"))?;
+        let event: EventKind = record
+            .event
+            .parse()
+            .with_context(|| format!("Invalid log record at {logfile}:{line_number}"))?;
+        let input = reconstruct_input(event, record.data)
+            .with_context(|| format!("Invalid {event} input at {logfile}:{line_number}"))?;
+
+        total += 1;
+        out.h1(&format!("Entry {total}: {event}"))?;
+        let input_json = serde_json::to_string(&input)?;
+        let execution = execute_hook(&mut out, &opts, &input_json, &input)?;
+        if opts.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string(&execution)?);
+        }
+
+        outputs.extend(execution.parsed_output.clone());
+
+        out.h1("Replay")?;
+        match failure(&opts, &execution) {
+            Some(reason) => {
+                failed += 1;
+                out.error(&format!("✗ Entry {total} failed: {reason}"))?;
+            }
+            None => out.success(&format!("✓ Entry {total} passed"))?,
+        }
+        out.newline()?;
+    }
+
+    let feedback = collect_feedback(&outputs);
+    if !feedback.is_empty() {
+        out.h1("What Claude/User Would See")?;
+        for message in &feedback {
+            out.writeln(message)?;
+        }
+    }
+
+    out.h1("Summary")?;
+    let summary = format!("{} passed, {failed} failed", total - failed);
+    let color = if failed == 0 {
+        Color::Green
+    } else {
+        Color::Red
+    };
+    out.color_line(&summary, color, true)?;
+
+    if failed > 0 {
+        anyhow::bail!("{failed} of {total} replayed entries failed");
+    }
+    Ok(())
+}
+
+/// Rebuild the hook input for `event` from its logged data, checking the
+/// data is a valid input for that event
+fn reconstruct_input(event: EventKind, data: Value) -> Result<Value> {
+    match event {
+        EventKind::PreToolUse => roundtrip::<PreToolUse>(data),
+        EventKind::PostToolUse => roundtrip::<PostToolUse>(data),
+        EventKind::Notification => roundtrip::<Notification>(data),
+        EventKind::Stop => roundtrip::<Stop>(data),
+        EventKind::SubagentStop => roundtrip::<SubagentStop>(data),
+        EventKind::UserPromptSubmit => roundtrip::<UserPromptSubmit>(data),
+    }
+}
+
+fn roundtrip<T: DeserializeOwned + Serialize>(data: Value) -> Result<Value> {
+    let input: T = serde_json::from_value(data)?;
+    Ok(serde_json::to_value(input)?)
+}
+
+/// Why a replayed entry failed, if it did
+fn failure(opts: &ExecOptions, execution: &HookExecution) -> Option<String> {
+    if let Some(failure) = opts.failure(execution) {
+        return Some(failure);
+    }
+    match execution.exit_code {
+        Some(0 | 2) => None,
+        Some(code) => Some(format!("hook exited with code {code}")),
+        None => Some("hook was killed by a signal".to_string()),
+    }
+}
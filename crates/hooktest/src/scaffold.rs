@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use code_hooks::EventKind;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where a scaffolded hook gets code-hooks from.
+///
+/// The templates use APIs newer than the published crate, so this defaults to
+/// the git repository rather than crates.io.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dependency {
+    Git(String),
+    /// A local checkout of the code-hooks crate, which must be absolute since
+    /// Cargo resolves it relative to the new project
+    Path(PathBuf),
+}
+
+impl Default for Dependency {
+    fn default() -> Self {
+        Dependency::Git(env!("CARGO_PKG_REPOSITORY").to_string())
+    }
+}
+
+impl Dependency {
+    /// The value of the `code-hooks` key in the dependencies table
+    fn toml(&self) -> String {
+        // Debug formatting quotes and escapes the value the same way as a
+        // TOML basic string for anything short of control characters
+        match self {
+            Dependency::Git(url) => format!("{{ git = {url:?} }}"),
+            Dependency::Path(path) => format!("{{ path = {:?} }}", path.display().to_string()),
+        }
+    }
+}
+
+/// Write a new Cargo project in `dir` with a hook for `event` built on
+/// code-hooks from `dependency`, named `name`
+pub fn write_scaffold(
+    dir: &Path,
+    name: &str,
+    event: EventKind,
+    dependency: &Dependency,
+) -> Result<()> {
+    check_name(name)?;
+    if dir.exists() {
+        anyhow::bail!("{} already exists", dir.display());
+    }
+
+    let src = dir.join("src");
+    fs::create_dir_all(&src).with_context(|| format!("Failed to create {}", src.display()))?;
+    fs::write(dir.join("Cargo.toml"), cargo_toml(name, dependency))?;
+    fs::write(src.join("main.rs"), main_rs(event))?;
+    fs::write(dir.join(".gitignore"), "/target\n")?;
+    Ok(())
+}
+
+/// Cargo package names are limited to ASCII letters, digits, `-` and `_`
+fn check_name(name: &str) -> Result<()> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || !valid {
+        anyhow::bail!("Invalid hook name '{name}': use letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+fn cargo_toml(name: &str, dependency: &Dependency) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2024"
+
+[dependencies]
+code-hooks = {}
+"#,
+        dependency.toml()
+    )
+}
+
+/// A minimal working hook for `event`
+fn main_rs(event: EventKind) -> &'static str {
+    match event {
+        EventKind::PreToolUse => {
+            r#"use code_hooks::{HookResponse, Input, PreToolUse, Result};
+
+fn main() -> Result<()> {
+    let input = PreToolUse::read()?;
+
+    if let Some(command) = input.bash_command()
+        && command.contains("rm -rf")
+    {
+        input.block("Refusing to run rm -rf").respond();
+    }
+
+    input.passthrough().respond()
+}
+"#
+        }
+        EventKind::PostToolUse => {
+            r#"use code_hooks::{HookResponse, Input, PostToolUse, Result};
+
+fn main() -> Result<()> {
+    let input = PostToolUse::read()?;
+
+    if let Some(file_path) = input.file_path() {
+        eprintln!("{} changed {file_path}", input.tool_name);
+    }
+
+    input.passthrough().respond()
+}
+"#
+        }
+        EventKind::Notification => {
+            r#"use code_hooks::{HookResponse, Input, Notification, Result};
+
+fn main() -> Result<()> {
+    let input = Notification::read()?;
+
+    eprintln!("Notification: {}", input.message);
+
+    Notification::passthrough().respond()
+}
+"#
+        }
+        EventKind::Stop => {
+            r#"use code_hooks::{HookResponse, Input, Result, Stop};
+
+fn main() -> Result<()> {
+    let input = Stop::read()?;
+
+    // Don't block again if this hook already kept Claude going
+    if let Some(output) = input.guard_allow() {
+        output.respond();
+    }
+
+    input.allow().respond()
+}
+"#
+        }
+        EventKind::SubagentStop => {
+            r#"use code_hooks::{HookResponse, Input, Result, SubagentStop};
+
+fn main() -> Result<()> {
+    let input = SubagentStop::read()?;
+
+    // Don't block again if this hook already kept the subagent going
+    if let Some(output) = input.guard_allow() {
+        output.respond();
+    }
+
+    input.allow().respond()
+}
+"#
+        }
+        EventKind::UserPromptSubmit => {
+            r#"use code_hooks::{HookResponse, Input, Result, UserPromptSubmit};
+
+fn main() -> Result<()> {
+    let input = UserPromptSubmit::read()?;
+
+    if input.prompt.contains("password") {
+        input.block("Prompts must not contain passwords").respond();
+    }
+
+    input.passthrough().respond()
+}
+"#
+        }
+    }
+}
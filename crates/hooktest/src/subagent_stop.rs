@@ -1,35 +1,32 @@
 use crate::color::ColorMode;
-use crate::execute::execute_hook;
-use crate::output::Output;
+use crate::execute::{ExecOptions, HookExecution, execute_hook};
 use anyhow::Result;
-use code_hooks::SubagentStop;
+use code_hooks::{EventKind, SubagentStop};
 
 pub fn run_subagent_stop_hook(
     session_id: String,
     transcript_path: String,
     stop_hook_active: bool,
-    hook_args: Vec<String>,
+    opts: &ExecOptions,
     color_mode: ColorMode,
-) -> Result<()> {
-    let mut out = Output::new(color_mode);
+) -> Result<HookExecution> {
+    let mut out = opts.output(color_mode);
 
     // Create the hook input using the SubagentStop struct
     let hook_input = SubagentStop {
         session_id,
         transcript_path,
+        hook_event_name: Some(EventKind::SubagentStop.hook_event_name().to_string()),
         stop_hook_active,
     };
 
-    // Serialize to JSON
-    let input_json = serde_json::to_string(&hook_input)?;
+    // Serialize to JSON, unless a captured payload was provided
+    let input_json = opts.input_json(&hook_input)?;
+    let input_value: serde_json::Value = serde_json::from_str(&input_json)?;
 
     // Execute the hook and parse output
-    if let Some(hook_output) = execute_hook(
-        &mut out,
-        &hook_args,
-        &input_json,
-        &serde_json::to_value(&hook_input)?,
-    )? {
+    let execution = execute_hook(&mut out, opts, &input_json, &input_value)?;
+    if let Some(hook_output) = &execution.parsed_output {
         out.h1("What Claude/User Would See")?;
 
         // Parse decision field
@@ -52,6 +49,13 @@ pub fn run_subagent_stop_hook(
             out.dimmed("Decision: NONE (Subagent stops normally)")?;
         }
 
+        if let Some(rule_id) = hook_output
+            .pointer("/metadata/ruleId")
+            .and_then(|r| r.as_str())
+        {
+            out.label("Rule", rule_id)?;
+        }
+
         if hook_output.get("continue").and_then(|c| c.as_bool()) == Some(false) {
             out.newline()?;
             out.error("Subagent would STOP processing")?;
@@ -65,7 +69,9 @@ pub fn run_subagent_stop_hook(
             out.newline()?;
             out.dimmed("Output would be hidden in transcript mode")?;
         }
+
+        opts.annotate_output(&mut out, hook_output)?;
     }
 
-    Ok(())
+    Ok(execution)
 }
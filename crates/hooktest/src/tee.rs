@@ -0,0 +1,68 @@
+use crate::color::ColorMode;
+use crate::execute::{ExecOptions, OutputFormat, execute_hook};
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::BufReader;
+
+/// Read JSON payloads from a named pipe and forward each one to the hook.
+///
+/// The pipe is reopened whenever its writer closes, so each hook invocation
+/// that writes to it is picked up in turn. If `count` is given, stop after
+/// that many payloads have been forwarded. With `--format json`, one JSON
+/// object is printed per payload.
+///
+/// A payload that isn't valid JSON, times out or misses an expectation is
+/// reported and forwarding carries on, so live traffic isn't lost; the
+/// failures are returned once the stream ends. After invalid JSON, the pipe is
+/// reopened.
+pub fn run_tee(
+    fifo: String,
+    count: Option<usize>,
+    opts: ExecOptions,
+    color_mode: ColorMode,
+) -> Result<()> {
+    let mut out = opts.output(color_mode);
+    let mut seen = 0;
+    let mut failed = 0;
+
+    while count.is_none_or(|count| seen < count) {
+        let file = File::open(&fifo).with_context(|| format!("Failed to open fifo {fifo}"))?;
+        let payloads = serde_json::Deserializer::from_reader(BufReader::new(file))
+            .into_iter::<serde_json::Value>();
+
+        for payload in payloads {
+            seen += 1;
+            out.h1(&format!("Payload {seen}"))?;
+            let payload = match payload {
+                Ok(payload) => payload,
+                Err(e) => {
+                    failed += 1;
+                    out.error(&format!("✗ Payload {seen} failed: invalid JSON: {e}"))?;
+                    out.newline()?;
+                    // The stream can't be resynchronized after invalid JSON,
+                    // so start again from a fresh open of the pipe
+                    break;
+                }
+            };
+            let input_json = serde_json::to_string(&payload)?;
+            let execution = execute_hook(&mut out, &opts, &input_json, &payload)?;
+            if opts.format == OutputFormat::Json {
+                println!("{}", serde_json::to_string(&execution)?);
+            }
+            if let Some(reason) = opts.failure(&execution) {
+                failed += 1;
+                out.error(&format!("✗ Payload {seen} failed: {reason}"))?;
+                out.newline()?;
+            }
+
+            if count.is_some_and(|count| seen >= count) {
+                break;
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} of {seen} payloads failed");
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use crate::color::ColorMode;
+use crate::output::Output;
+use anyhow::{Context, Result};
+use claude_transcript::analysis::{tool_frequency, tool_uses, total_usage};
+use claude_transcript::anonymize::{AnonymizeOptions, anonymize};
+use claude_transcript::parse::{parse_transcript_with_context, validate_transcript_streaming};
+use claude_transcript::schema::validate_for_version;
+use claude_transcript::{ContentBlock, MessageContent, TranscriptEntry, TranscriptMessage};
+use semver::Version;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::BufReader;
+
+/// Longest excerpt of message text shown per entry
+const EXCERPT_LEN: usize = 100;
+
+/// Transcript entry types, for filtering the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EntryType {
+    User,
+    Assistant,
+    System,
+    Summary,
+}
+
+impl EntryType {
+    fn of(entry: &TranscriptEntry) -> Self {
+        match entry {
+            TranscriptEntry::User(_) => EntryType::User,
+            TranscriptEntry::Assistant(_) => EntryType::Assistant,
+            TranscriptEntry::System(_) => EntryType::System,
+            TranscriptEntry::Summary(_) => EntryType::Summary,
+        }
+    }
+}
+
+/// What the transcript subcommand should do with the transcripts
+pub struct TranscriptOptions {
+    /// Print a de-identified JSONL copy instead of displaying entries
+    pub anonymize: bool,
+    /// When anonymizing, also replace tool outputs with a placeholder
+    pub strip_tool_outputs: bool,
+    /// Print a frequency table of tool names instead of displaying entries
+    pub tool_summary: bool,
+    /// Check entries against the fields this Claude Code version writes
+    pub schema_version: Option<Version>,
+    /// Only check that every line parses, failing if any don't
+    pub strict: bool,
+    /// Only display entries of these types; all types if empty
+    pub only: Vec<EntryType>,
+    /// Print counts of entries, tool uses, tokens and parse errors per file
+    pub stats: bool,
+}
+
+/// Display or transform one or more transcript files
+pub fn run_transcript(
+    paths: Vec<String>,
+    opts: TranscriptOptions,
+    color_mode: ColorMode,
+) -> Result<()> {
+    if opts.anonymize {
+        for path in &paths {
+            print_anonymized(path, &opts)?;
+        }
+        return Ok(());
+    }
+
+    let mut out = Output::new(color_mode);
+    if opts.tool_summary {
+        return print_tool_summary(&mut out, &paths);
+    }
+    if let Some(version) = &opts.schema_version {
+        return check_schema(&mut out, &paths, version);
+    }
+    if opts.strict {
+        return validate_strict(&mut out, &paths);
+    }
+    if opts.stats {
+        for path in &paths {
+            print_stats(&mut out, path)?;
+        }
+        return Ok(());
+    }
+    for path in &paths {
+        display_single_transcript(&mut out, path, &opts.only)?;
+    }
+    Ok(())
+}
+
+fn read(path: &str) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read transcript {path}"))
+}
+
+/// Print an anonymized copy of the transcript as JSONL on stdout
+fn print_anonymized(path: &str, opts: &TranscriptOptions) -> Result<()> {
+    let result = parse_transcript_with_context(&read(path)?);
+    for error in &result.errors {
+        eprintln!(
+            "Warning: skipping {path}:{}: {}",
+            error.line_number, error.json_error
+        );
+    }
+
+    let anonymized = anonymize(
+        result.entries,
+        AnonymizeOptions {
+            strip_tool_outputs: opts.strip_tool_outputs,
+            ..Default::default()
+        },
+    )
+    .context("Failed to anonymize transcript")?;
+    for entry in anonymized {
+        println!("{}", serde_json::to_string(&entry)?);
+    }
+    Ok(())
+}
+
+/// Print how many times each tool is used across all the transcripts
+fn print_tool_summary(out: &mut Output, paths: &[String]) -> Result<()> {
+    let mut entries = Vec::new();
+    for path in paths {
+        let result = parse_transcript_with_context(&read(path)?);
+        for error in &result.errors {
+            eprintln!(
+                "Warning: skipping {path}:{}: {}",
+                error.line_number, error.json_error
+            );
+        }
+        entries.extend(result.entries);
+    }
+
+    let frequency = tool_frequency(&entries);
+    out.h1("Tool Summary")?;
+    if frequency.is_empty() {
+        out.dimmed("No tool uses found")?;
+        return Ok(());
+    }
+    let width = frequency.keys().map(|name| name.len()).max().unwrap_or(0);
+    for (name, count) in &frequency {
+        out.writeln(&format!("{name:<width$}  {count}"))?;
+    }
+    Ok(())
+}
+
+/// Report entries missing fields that Claude Code `version` writes, failing if
+/// any are found
+fn check_schema(out: &mut Output, paths: &[String], version: &Version) -> Result<()> {
+    let mut total = 0;
+    for path in paths {
+        let content = read(path)?;
+        let mut line_numbers = Vec::new();
+        let mut entries = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Value>(line) {
+                Ok(entry) => {
+                    line_numbers.push(index + 1);
+                    entries.push(entry);
+                }
+                Err(e) => eprintln!("Warning: skipping {path}:{}: {e}", index + 1),
+            }
+        }
+
+        out.h1(path)?;
+        let issues = validate_for_version(&entries, version);
+        if issues.is_empty() {
+            out.success(&format!("✓ Matches the Claude Code {version} schema"))?;
+            out.newline()?;
+        }
+        for issue in &issues {
+            out.error(&format!("line {}: {issue}", line_numbers[issue.index]))?;
+            out.newline()?;
+        }
+        total += issues.len();
+    }
+
+    if total > 0 {
+        let noun = if total == 1 { "issue" } else { "issues" };
+        anyhow::bail!("{total} schema {noun} found for Claude Code {version}");
+    }
+    Ok(())
+}
+
+/// Report every line that fails to parse, streaming each file rather than
+/// loading it, and fail if there are any
+fn validate_strict(out: &mut Output, paths: &[String]) -> Result<()> {
+    let mut total = 0;
+    for path in paths {
+        let file = File::open(path).with_context(|| format!("Failed to read transcript {path}"))?;
+        let errors = validate_transcript_streaming(BufReader::new(file));
+
+        out.h1(path)?;
+        if errors.is_empty() {
+            out.success("✓ All lines parse")?;
+            out.newline()?;
+        }
+        for error in &errors {
+            out.error(&format!("line {}: {}", error.line_number, error.json_error))?;
+            out.newline()?;
+        }
+        total += errors.len();
+    }
+
+    if total > 0 {
+        let noun = if total == 1 { "line" } else { "lines" };
+        anyhow::bail!("{total} transcript {noun} failed to parse");
+    }
+    Ok(())
+}
+
+/// Print how many entries of each type a transcript has, with its tool uses,
+/// token usage and parse errors
+fn print_stats(out: &mut Output, path: &str) -> Result<()> {
+    let result = parse_transcript_with_context(&read(path)?);
+
+    out.h1(path)?;
+    for (kind, entry_type) in [
+        ("user", EntryType::User),
+        ("assistant", EntryType::Assistant),
+        ("system", EntryType::System),
+        ("summary", EntryType::Summary),
+    ] {
+        let count = result
+            .entries
+            .iter()
+            .filter(|entry| EntryType::of(entry) == entry_type)
+            .count();
+        out.label(kind, &count.to_string())?;
+    }
+
+    let usage = total_usage(&result.entries);
+    let input = usage.input_tokens.unwrap_or_default()
+        + usage.cache_creation_input_tokens.unwrap_or_default()
+        + usage.cache_read_input_tokens.unwrap_or_default();
+    let output = usage.output_tokens.unwrap_or_default();
+    out.label("Tool uses", &tool_uses(&result.entries).len().to_string())?;
+    out.label(
+        "Tokens",
+        &format!("{} ({input} input, {output} output)", input + output),
+    )?;
+    out.label("Parse errors", &result.errors.len().to_string())?;
+    Ok(())
+}
+
+/// Print a one-line description of each entry, followed by any parse errors
+fn display_single_transcript(out: &mut Output, path: &str, only: &[EntryType]) -> Result<()> {
+    let result = parse_transcript_with_context(&read(path)?);
+
+    out.h1(path)?;
+    let shown = result
+        .entries
+        .iter()
+        .filter(|entry| only.is_empty() || only.contains(&EntryType::of(entry)));
+    for entry in shown {
+        let (kind, description) = describe(entry);
+        out.label(kind, &description)?;
+    }
+
+    if !result.is_ok() {
+        out.h1("Parse Errors")?;
+        for error in &result.errors {
+            out.error(&format!("line {}: {}", error.line_number, error.json_error))?;
+            out.newline()?;
+        }
+    }
+    Ok(())
+}
+
+/// The entry type and a short summary of its contents
+fn describe(entry: &TranscriptEntry) -> (&'static str, String) {
+    match entry {
+        TranscriptEntry::User(user) => ("user", describe_message(&user.message)),
+        TranscriptEntry::Assistant(assistant) => {
+            ("assistant", describe_message(&assistant.message))
+        }
+        TranscriptEntry::System(system) => {
+            let level = system.level.as_deref().unwrap_or("info");
+            ("system", format!("[{level}] {}", excerpt(&system.content)))
+        }
+        TranscriptEntry::Summary(summary) => ("summary", excerpt(&summary.summary)),
+    }
+}
+
+fn describe_message(message: &TranscriptMessage) -> String {
+    let mut parts = Vec::new();
+    match message.content() {
+        Some(MessageContent::Text(text)) => parts.push(excerpt(text)),
+        Some(MessageContent::Blocks(blocks)) => {
+            for block in blocks {
+                match block {
+                    ContentBlock::Text { text } => parts.push(excerpt(text)),
+                    ContentBlock::ToolUse { name, .. } => parts.push(format!("→ {name}")),
+                    ContentBlock::ToolResult { content, .. } => {
+                        parts.push(format!("tool result: {}", excerpt(&content.joined_text())))
+                    }
+                    ContentBlock::Thinking { .. } => parts.push("(thinking)".to_string()),
+                }
+            }
+        }
+        None => {}
+    }
+    if let TranscriptMessage::Assistant {
+        tool_uses: Some(tool_uses),
+        ..
+    } = message
+    {
+        parts.extend(
+            tool_uses
+                .iter()
+                .map(|tool_use| format!("→ {}", tool_use.tool_name)),
+        );
+    }
+    parts.join(" ")
+}
+
+/// The first line of `text`, truncated to `EXCERPT_LEN` characters
+fn excerpt(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > EXCERPT_LEN {
+        let truncated: String = line.chars().take(EXCERPT_LEN).collect();
+        format!("{truncated}…")
+    } else {
+        line.to_string()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::color::ColorMode;
+use crate::execute::{ExecOptions, HookExecution, execute_hook};
+use anyhow::Result;
+use code_hooks::{EventKind, UserPromptSubmit};
+
+pub fn run_user_prompt_submit_hook(
+    session_id: String,
+    transcript_path: String,
+    prompt: String,
+    opts: &ExecOptions,
+    color_mode: ColorMode,
+) -> Result<HookExecution> {
+    let mut out = opts.output(color_mode);
+
+    // Create the hook input using the UserPromptSubmit struct
+    let hook_input = UserPromptSubmit {
+        session_id,
+        transcript_path,
+        hook_event_name: Some(EventKind::UserPromptSubmit.hook_event_name().to_string()),
+        prompt,
+    };
+
+    // Serialize to JSON, unless a captured payload was provided
+    let input_json = opts.input_json(&hook_input)?;
+    let input_value: serde_json::Value = serde_json::from_str(&input_json)?;
+
+    // Execute the hook and parse output
+    let execution = execute_hook(&mut out, opts, &input_json, &input_value)?;
+    if let Some(hook_output) = &execution.parsed_output {
+        out.h1("What Claude/User Would See")?;
+
+        // Parse decision field
+        if let Some(decision) = hook_output.get("decision").and_then(|d| d.as_str()) {
+            match decision {
+                "block" => {
+                    out.write("Decision: ")?;
+                    out.error("BLOCK")?;
+                    out.newline()?;
+
+                    if let Some(reason) = hook_output.get("reason").and_then(|r| r.as_str()) {
+                        out.label("User sees", reason)?;
+                    }
+                    out.dimmed("Claude sees: (nothing, the prompt is erased)")?;
+                }
+                _ => {
+                    out.label("Decision", &format!("Unknown ({decision})"))?;
+                }
+            }
+        } else {
+            out.dimmed("Decision: NONE (prompt is processed normally)")?;
+        }
+
+        if let Some(context) = hook_output
+            .pointer("/hookSpecificOutput/additionalContext")
+            .and_then(|c| c.as_str())
+        {
+            out.label("Context added for Claude", context)?;
+        }
+
+        if let Some(rule_id) = hook_output
+            .pointer("/metadata/ruleId")
+            .and_then(|r| r.as_str())
+        {
+            out.label("Rule", rule_id)?;
+        }
+
+        if hook_output.get("continue").and_then(|c| c.as_bool()) == Some(false) {
+            out.newline()?;
+            out.error("Claude would STOP processing")?;
+            out.newline()?;
+            if let Some(reason) = hook_output.get("stopReason").and_then(|r| r.as_str()) {
+                out.label("Stop reason shown to user", reason)?;
+            }
+        }
+
+        if hook_output.get("suppressOutput").and_then(|s| s.as_bool()) == Some(true) {
+            out.newline()?;
+            out.dimmed("Output would be hidden in transcript mode")?;
+        }
+
+        opts.annotate_output(&mut out, hook_output)?;
+    }
+
+    Ok(execution)
+}
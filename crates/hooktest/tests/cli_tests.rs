@@ -1,21 +1,22 @@
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 use predicates::str::contains;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::process::Command;
 use tempfile::{NamedTempFile, TempPath};
 
-fn make_hook_script() -> TempPath {
-    let mut file = NamedTempFile::new().unwrap();
-    fs::write(
-        file.path(),
-        "#!/bin/sh\ncat >/dev/null\nprintf '{\"decision\":\"approve\",\"reason\":\"ok\"}'\n",
-    )
-    .unwrap();
+fn make_script(body: &str) -> TempPath {
+    let file = NamedTempFile::new().unwrap();
+    fs::write(file.path(), format!("#!/bin/sh\n{body}\n")).unwrap();
     fs::set_permissions(file.path(), fs::Permissions::from_mode(0o755)).unwrap();
     file.into_temp_path()
 }
 
+fn make_hook_script() -> TempPath {
+    make_script("cat >/dev/null\nprintf '{\"decision\":\"approve\",\"reason\":\"ok\"}'")
+}
+
 #[test]
 fn test_help() {
     Command::cargo_bin("hooktest")
@@ -31,18 +32,41 @@ fn test_pretool() {
     let hook = make_hook_script();
     Command::cargo_bin("hooktest")
         .unwrap()
-        .args([
-            "pretool",
-            "--tool",
-            "Bash",
-            "--",
-            hook.to_str().unwrap(),
-        ])
+        .args(["pretool", "--tool", "Bash", "--", hook.to_str().unwrap()])
         .assert()
         .success()
         .stdout(contains("Decision: APPROVE"));
 }
 
+#[test]
+fn test_pretool_permission_decision() {
+    let hook = make_script(
+        r#"cat >/dev/null
+printf '{"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"deny","permissionDecisionReason":"nope"}}'"#,
+    );
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["pretool", "--", hook.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Permission Decision: DENY"))
+        .stdout(contains("Claude sees: nope"));
+}
+
+#[test]
+fn test_pretool_rule_id() {
+    let hook = make_script(
+        r#"cat >/dev/null
+printf '{"decision":"block","reason":"no","metadata":{"ruleId":"git-001"}}'"#,
+    );
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["pretool", "--", hook.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Rule: git-001"));
+}
+
 #[test]
 fn test_posttool() {
     let hook = make_hook_script();
@@ -93,3 +117,963 @@ fn test_subagent_stop() {
         .success()
         .stdout(contains("Hook Output (Parsed)"));
 }
+
+#[test]
+fn test_tee_fifo() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fifo = dir.path().join("hooks.fifo");
+    assert!(
+        Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success()
+    );
+
+    let hook = make_hook_script();
+    let child = Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "tee",
+            "--fifo",
+            fifo.to_str().unwrap(),
+            "--count",
+            "2",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    {
+        let mut writer = fs::OpenOptions::new().write(true).open(&fifo).unwrap();
+        writeln!(writer, r#"{{"session_id":"one","tool_name":"Bash"}}"#).unwrap();
+        writeln!(writer, r#"{{"session_id":"two","tool_name":"Read"}}"#).unwrap();
+    }
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("Running Hook").count(), 2);
+    assert!(stdout.contains("Payload 2"));
+    assert!(stdout.contains("\"session_id\": \"two\""));
+}
+
+#[test]
+fn test_tee_continues_after_failure() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fifo = dir.path().join("hooks.fifo");
+    assert!(
+        Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success()
+    );
+
+    // Only the first payload misses the expected decision
+    let hook = make_script(
+        r#"if grep -q one; then echo '{"decision":"block"}'; else echo '{"decision":"approve"}'; fi"#,
+    );
+    let child = Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--expect-decision",
+            "approve",
+            "tee",
+            "--fifo",
+            fifo.to_str().unwrap(),
+            "--count",
+            "2",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    {
+        let mut writer = fs::OpenOptions::new().write(true).open(&fifo).unwrap();
+        writeln!(writer, r#"{{"session_id":"one","tool_name":"Bash"}}"#).unwrap();
+        writeln!(writer, r#"{{"session_id":"two","tool_name":"Read"}}"#).unwrap();
+    }
+
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("Running Hook").count(), 2);
+    assert!(stdout.contains("Payload 1 failed"));
+    assert!(!stdout.contains("Payload 2 failed"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 of 2 payloads failed"));
+}
+
+#[test]
+fn test_tee_continues_after_invalid_json() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fifo = dir.path().join("hooks.fifo");
+    assert!(
+        Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success()
+    );
+
+    let hook = make_hook_script();
+    let mut child = Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "tee",
+            "--fifo",
+            fifo.to_str().unwrap(),
+            "--count",
+            "2",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    {
+        let mut writer = fs::OpenOptions::new().write(true).open(&fifo).unwrap();
+        writeln!(writer, "not json").unwrap();
+    }
+
+    // Wait for the failure to be reported before the next writer opens the
+    // pipe, so its payload is read from the reopened pipe
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut report = String::new();
+    while !report.contains("Payload 1 failed") {
+        assert_ne!(stdout.read_line(&mut report).unwrap(), 0, "{report}");
+    }
+
+    let mut writer = fs::OpenOptions::new().write(true).open(&fifo).unwrap();
+    writeln!(writer, r#"{{"session_id":"two","tool_name":"Read"}}"#).unwrap();
+    stdout.read_to_string(&mut report).unwrap();
+    let output = child.wait_with_output().unwrap();
+    drop(writer);
+
+    assert!(!output.status.success());
+    assert!(report.contains("invalid JSON"), "{report}");
+    assert!(report.contains("\"session_id\": \"two\""), "{report}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 of 2 payloads failed"));
+}
+
+/// A PreToolUse payload larger than a pipe buffer
+fn large_payload() -> NamedTempFile {
+    let payload = NamedTempFile::new().unwrap();
+    let input = serde_json::json!({
+        "session_id": "s",
+        "transcript_path": "/t",
+        "tool_name": "Bash",
+        "tool_input": {"command": "a".repeat(100 * 1024)},
+    });
+    fs::write(payload.path(), input.to_string()).unwrap();
+    payload
+}
+
+#[test]
+fn test_timeout_with_unread_large_input() {
+    let payload = large_payload();
+    let hook = make_script("sleep 10");
+
+    // assert_cmd's Command, to bound the test if hooktest hangs
+    assert_cmd::Command::cargo_bin("hooktest")
+        .unwrap()
+        .timeout(std::time::Duration::from_secs(8))
+        .args(["--timeout", "1", "pretool", "--input-file"])
+        .arg(payload.path())
+        .args(["--", hook.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("Hook timed out after 1s"));
+}
+
+#[test]
+fn test_large_output_before_reading_input() {
+    let payload = large_payload();
+    // Fills the stdout pipe before reading any input
+    let hook = make_script("head -c 200000 /dev/zero | tr '\\0' a\ncat >/dev/null");
+
+    assert_cmd::Command::cargo_bin("hooktest")
+        .unwrap()
+        .timeout(std::time::Duration::from_secs(30))
+        .args(["--timeout", "10", "pretool", "--input-file"])
+        .arg(payload.path())
+        .args(["--", hook.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Exit Code: 0"));
+}
+
+#[test]
+fn test_pretool_input_file() {
+    // The hook echoes back the command it received
+    let hook = make_script(
+        r#"command=$(sed -n 's/.*"command":"\([^"]*\)".*/\1/p')
+printf '{"decision":"block","reason":"saw %s"}' "$command""#,
+    );
+    let payload = NamedTempFile::new().unwrap();
+    fs::write(
+        payload.path(),
+        r#"{"session_id":"captured","transcript_path":"/tmp/t.jsonl","tool_name":"Bash","tool_input":{"command":"cargo test"}}"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "pretool",
+            "--input-file",
+            payload.path().to_str().unwrap(),
+            "--tool-input",
+            "command=ignored",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Claude sees: saw cargo test"))
+        .stderr(contains("Warning: --tool-input is ignored"));
+
+    // The same payload piped through stdin
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["pretool", "--input", "-", "--", hook.to_str().unwrap()])
+        .stdin(fs::File::open(payload.path()).unwrap())
+        .assert()
+        .success()
+        .stdout(contains("Claude sees: saw cargo test"));
+}
+
+#[test]
+fn test_timeout_kills_hook() {
+    let start = std::time::Instant::now();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["--timeout", "1", "stop", "--", "sleep", "10"])
+        .assert()
+        .failure()
+        .stdout(contains("Hook timed out after 1s and was killed"));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[test]
+fn test_compat_new_schema() {
+    let hook = make_script(
+        r#"cat >/dev/null
+printf '{"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"deny","permissionDecisionReason":"nope"}}'"#,
+    );
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["compat", "--", hook.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Legacy protocol sees: none"))
+        .stdout(contains("New protocol sees: deny"))
+        .stdout(contains("also set decision to 'block'"));
+}
+
+#[test]
+fn test_compat_legacy_schema() {
+    let hook = make_hook_script();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["compat", "--", hook.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Legacy protocol sees: approve"))
+        .stdout(contains("New protocol sees: allow"))
+        .stdout(contains("understood the same way"));
+}
+
+#[test]
+fn test_format_json() {
+    let hook = make_hook_script();
+    let output = Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["--format", "json", "pretool", "--", hook.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["exit_code"], 0);
+    assert_eq!(json["parsed_output"]["decision"], "approve");
+    assert_eq!(json["decision"], "approve");
+    assert_eq!(json["input"]["tool_name"], "Bash");
+}
+
+#[test]
+fn test_expect_passes() {
+    let hook = make_hook_script();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--expect-decision",
+            "approve",
+            "--expect-exit",
+            "0",
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    // New-protocol spelling of the same decision also matches
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--expect-decision",
+            "allow",
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_expect_fails_on_mismatch() {
+    let hook = make_hook_script();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--expect-decision",
+            "block",
+            "--expect-exit",
+            "2",
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("expected decision=block, got approve"))
+        .stderr(contains("expected exit=2, got 0"));
+}
+
+#[test]
+fn test_expect_json_subset() {
+    let hook = make_script(
+        r#"cat >/dev/null
+printf '{"decision":"block","reason":"no","metadata":{"ruleId":"git-001"}}'"#,
+    );
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--expect-json",
+            r#"{"decision":"block","metadata":{"ruleId":"git-001"}}"#,
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--expect-json",
+            r#"{"metadata":{"ruleId":"git-002"}}"#,
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            r#"expected json /metadata/ruleId="git-002", got "git-001""#,
+        ));
+}
+
+const TRANSCRIPT: &str = r#"{"type":"user","message":{"role":"user","content":"edit /home/alice/project/main.rs"},"uuid":"u1","timestamp":"2025-01-01T00:00:00Z","cwd":"/home/alice/project","sessionId":"real-session-id","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":null}
+{"type":"assistant","message":{"id":"msg_1","type":"message","role":"assistant","model":"test-model","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}],"stop_reason":null,"stop_sequence":null,"usage":{"input_tokens":1,"output_tokens":1}},"uuid":"a1","timestamp":"2025-01-01T00:00:01Z","cwd":"/home/alice/project","sessionId":"real-session-id","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"u1","requestId":"req-1"}
+{"type":"system","uuid":"s1","timestamp":"2025-01-01T00:00:02Z","content":"Limit reached","cwd":"/home/alice/project","sessionId":"real-session-id","version":"1.0.0","userType":"external","isSidechain":false,"parentUuid":"a1","isMeta":false}"#;
+
+fn make_transcript() -> NamedTempFile {
+    let file = NamedTempFile::new().unwrap();
+    fs::write(file.path(), TRANSCRIPT).unwrap();
+    file
+}
+
+#[test]
+fn test_transcript_display() {
+    let transcript = make_transcript();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", transcript.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("user: edit /home/alice/project/main.rs"))
+        .stdout(contains("assistant: → Bash"))
+        .stdout(contains("system: [info] Limit reached"));
+}
+
+#[test]
+fn test_transcript_only() {
+    let transcript = make_transcript();
+    let path = transcript.path().to_str().unwrap();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", "--only", "system", path])
+        .assert()
+        .success()
+        .stdout(contains("system: [info] Limit reached"))
+        .stdout(contains("user:").not())
+        .stdout(contains("assistant:").not());
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", "--only", "user,summary", path])
+        .assert()
+        .success()
+        .stdout(contains("user: edit"))
+        .stdout(contains("system:").not());
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", "--only", "system,tool", path])
+        .assert()
+        .failure()
+        .stderr(contains("invalid value 'tool' for '--only <TYPES>'"));
+}
+
+#[test]
+fn test_transcript_stats() {
+    let transcript = NamedTempFile::new().unwrap();
+    fs::write(
+        transcript.path(),
+        format!("{TRANSCRIPT}\n{{\"type\":\"summary\",\"summ"),
+    )
+    .unwrap();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["--no-color", "transcript", "--stats"])
+        .arg(transcript.path())
+        .assert()
+        .success()
+        .stdout(contains("user: 1\nassistant: 1\nsystem: 1\nsummary: 0\n"))
+        .stdout(contains("Tool uses: 1\n"))
+        .stdout(contains("Tokens: 2 (1 input, 1 output)\n"))
+        .stdout(contains("Parse errors: 1\n"))
+        .stdout(contains("Bash").not());
+}
+
+#[test]
+fn test_transcript_tool_summary() {
+    let transcript = make_transcript();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "transcript",
+            "--tool-summary",
+            transcript.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Tool Summary"))
+        .stdout(predicates::str::is_match(r"(?m)^Bash\s+1$").unwrap());
+}
+
+#[test]
+fn test_transcript_schema_version() {
+    let transcript = make_transcript();
+    let path = transcript.path().to_str().unwrap();
+
+    // The system entry has no `level`, which 1.0.35 and later write
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", "--schema-version", "1.0.43", path])
+        .assert()
+        .failure()
+        .stdout(contains(
+            "line 3: system entry is missing `level` (required since 1.0.35)",
+        ))
+        .stderr(contains("1 schema issue found for Claude Code 1.0.43"));
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", "--schema-version", "1.0.16", path])
+        .assert()
+        .success()
+        .stdout(contains("Matches the Claude Code 1.0.16 schema"));
+}
+
+#[test]
+fn test_transcript_strict() {
+    let transcript = make_transcript();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "transcript",
+            "--strict",
+            transcript.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("All lines parse"));
+
+    let broken = NamedTempFile::new().unwrap();
+    fs::write(broken.path(), format!("{TRANSCRIPT}\nnot json\n{{}}\n")).unwrap();
+    let lines = TRANSCRIPT.lines().count();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["transcript", "--strict", broken.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(contains(format!("line {}: ", lines + 1)))
+        .stdout(contains(format!("line {}: ", lines + 2)))
+        .stderr(contains("2 transcript lines failed to parse"));
+}
+
+#[test]
+fn test_transcript_anonymize() {
+    let transcript = make_transcript();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .env("HOME", "/home/alice")
+        .args([
+            "transcript",
+            "--anonymize",
+            transcript.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains(r#""cwd":"~/project""#))
+        .stdout(contains("edit ~/project/main.rs"))
+        .stdout(contains("/home/alice").not())
+        .stdout(contains("real-session-id").not());
+}
+
+#[test]
+fn test_replay_logged_events() {
+    let log = NamedTempFile::new().unwrap();
+    let log_path = log.path().to_str().unwrap();
+
+    for (event, payload) in [
+        (
+            "pretool",
+            r#"{"session_id":"s1","transcript_path":"/t","tool_name":"Bash","tool_input":{"command":"ls"}}"#,
+        ),
+        (
+            "stop",
+            r#"{"session_id":"s1","transcript_path":"/t","stop_hook_active":false}"#,
+        ),
+    ] {
+        let input = NamedTempFile::new().unwrap();
+        fs::write(input.path(), payload).unwrap();
+        Command::cargo_bin("hooktest")
+            .unwrap()
+            .args(["log", event, log_path])
+            .stdin(fs::File::open(input.path()).unwrap())
+            .assert()
+            .success();
+    }
+
+    let hook = make_hook_script();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["replay", log_path, "--", hook.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Entry 1: pretool"))
+        .stdout(contains("Entry 2: stop"))
+        .stdout(contains("What Claude/User Would See"))
+        .stdout(contains(r#""command": "ls""#))
+        .stdout(contains("2 passed, 0 failed"));
+
+    let failing = make_script("cat >/dev/null\nexit 1");
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["replay", log_path, "--", failing.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(contains("✗ Entry 1 failed: hook exited with code 1"))
+        .stdout(contains("0 passed, 2 failed"));
+}
+
+#[test]
+fn test_pretool_show_diff() {
+    let hook = make_hook_script();
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--no-color",
+            "pretool",
+            "--tool",
+            "Edit",
+            "--tool-input",
+            "file_path=/src/main.rs",
+            "--tool-input",
+            "old_string=let x = 1;",
+            "--tool-input",
+            "new_string=let x = 2;",
+            "--show-diff",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Edit Diff"))
+        .stdout(contains("- let x = 1;"))
+        .stdout(contains("+ let x = 2;"))
+        .stdout(contains("Input JSON").not());
+}
+
+#[test]
+fn test_render_spec() {
+    let hook = make_script(
+        "cat >/dev/null\nprintf '{\"decision\":\"block\",\"reason\":\"no\",\"severity\":\"high\",\"ticket\":42}'",
+    );
+    let spec = NamedTempFile::new().unwrap();
+    fs::write(
+        spec.path(),
+        r#"{"severity": "How serious the policy violation is"}"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--no-color",
+            "--render-spec",
+            spec.path().to_str().unwrap(),
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains(
+            r#"severity: How serious the policy violation is ("high")"#,
+        ))
+        .stdout(contains("ticket: not described by the render spec"));
+}
+
+#[test]
+fn test_user_prompt_submit_blocks_banned_word() {
+    let hook = make_script(
+        r#"if grep -q password; then
+  printf '{"decision":"block","reason":"Prompts must not contain secrets"}'
+else
+  printf '{"hookSpecificOutput":{"hookEventName":"UserPromptSubmit","additionalContext":"Current branch: main"}}'
+fi"#,
+    );
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "userpromptsubmit",
+            "--prompt",
+            "my password is hunter2",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("BLOCK"))
+        .stdout(contains("User sees: Prompts must not contain secrets"));
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "userpromptsubmit",
+            "--prompt",
+            "fix the tests",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Context added for Claude: Current branch: main"));
+}
+
+#[test]
+fn test_env_passthrough() {
+    let hook = make_script(
+        r#"cat >/dev/null
+printf '{"decision":"approve","reason":"%s in %s"}' "$HOOK_MODE" "$CLAUDE_PROJECT_DIR""#,
+    );
+    let project = tempfile::tempdir().unwrap();
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .current_dir(project.path())
+        .args([
+            "--env",
+            "HOOK_MODE=strict",
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "strict in {}",
+            project.path().canonicalize().unwrap().display()
+        )));
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--env",
+            "HOOK_MODE=lenient",
+            "--env",
+            "CLAUDE_PROJECT_DIR=/work/project",
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("lenient in /work/project"));
+}
+
+#[test]
+fn test_compact_input() {
+    let hook = make_script("cat >/dev/null; echo '{}'");
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--compact-input",
+            "--no-color",
+            "pretool",
+            "--tool-input",
+            "command=ls",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::is_match(
+                r#"=== Input JSON ===\n\{[^\n]*"tool_input":\{"command":"ls"\}[^\n]*\}\n=== Execution"#,
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn test_stream_stderr() {
+    let hook =
+        make_script("cat >/dev/null; echo 'step 1 of 2' >&2; echo 'step 2 of 2' >&2; echo '{}'");
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--stream",
+            "--no-color",
+            "stop",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stderr(contains("step 1 of 2\nstep 2 of 2"))
+        .stdout(contains("STDERR").not());
+}
+
+#[test]
+fn test_no_input_echo() {
+    let hook = make_script("grep -q s3cret && echo '{\"decision\":\"approve\"}'");
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--no-input-echo",
+            "--no-color",
+            "pretool",
+            "--tool-input",
+            "command=export TOKEN=s3cret",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Input JSON").not())
+        .stdout(contains("s3cret").not())
+        .stdout(contains(r#""decision": "approve""#));
+}
+
+#[test]
+fn test_repeat_reports_timing() {
+    let runs = NamedTempFile::new().unwrap();
+    let hook = make_script(&format!(
+        "cat >/dev/null\necho run >> {}\nprintf '{{\"decision\":\"approve\"}}'",
+        runs.path().display()
+    ));
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args([
+            "--repeat",
+            "3",
+            "--no-color",
+            "--expect-decision",
+            "approve",
+            "pretool",
+            "--",
+            hook.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Runs: 3"))
+        .stdout(
+            predicate::str::is_match(
+                r"Duration: min [\d.]+ms, median [\d.]+ms, max [\d.]+ms, mean [\d.]+ms",
+            )
+            .unwrap(),
+        )
+        .stdout(contains("STDOUT").not());
+
+    assert_eq!(fs::read_to_string(runs.path()).unwrap(), "run\nrun\nrun\n");
+}
+
+#[test]
+fn test_log_records_duration_and_response() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(
+        input.path(),
+        r#"{"session_id":"s1","transcript_path":"/tmp/t.jsonl","tool_name":"Bash","tool_input":{"command":"ls"}}"#,
+    )
+    .unwrap();
+    let log = NamedTempFile::new().unwrap();
+
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .args(["log", "pretool", log.path().to_str().unwrap()])
+        .stdin(fs::File::open(input.path()).unwrap())
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    let line = fs::read_to_string(log.path()).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+    assert_eq!(entry["event"], "pretool");
+    assert_eq!(entry["data"]["tool_input"]["command"], "ls");
+    assert!(entry["duration_ms"].is_u64());
+    assert_eq!(entry["response"], serde_json::json!({}));
+}
+
+#[test]
+fn test_log_redact() {
+    for (command, redacted) in [
+        ("deploy --token=abc123 --verbose", "deploy *** --verbose"),
+        ("deploy --token abc123 --verbose", "deploy *** --verbose"),
+        ("login --password hunter2", "login ***"),
+        (r#"login --api-key "abc 123""#, "login ***"),
+        (
+            r#"curl -H "X-Api-Key: abc123" https://example.com"#,
+            r#"curl -H "***" https://example.com"#,
+        ),
+        (
+            r#"curl -H 'Authorization: token ghp_abc123' https://example.com"#,
+            "curl -H '***' https://example.com",
+        ),
+        ("deploy --verbose", "deploy --verbose"),
+    ] {
+        let input = NamedTempFile::new().unwrap();
+        let payload = serde_json::json!({
+            "session_id": "s1",
+            "transcript_path": "/tmp/t.jsonl",
+            "tool_name": "Bash",
+            "tool_input": {"command": command},
+        });
+        fs::write(input.path(), payload.to_string()).unwrap();
+        let log = NamedTempFile::new().unwrap();
+
+        Command::cargo_bin("hooktest")
+            .unwrap()
+            .args(["log", "pretool", log.path().to_str().unwrap(), "--redact"])
+            .stdin(fs::File::open(input.path()).unwrap())
+            .assert()
+            .success();
+
+        let line = fs::read_to_string(log.path()).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(
+            entry["data"]["tool_input"]["command"], redacted,
+            "{command}"
+        );
+    }
+}
+
+#[test]
+fn test_new_scaffold() {
+    let dir = tempfile::tempdir().unwrap();
+    let code_hooks = concat!(env!("CARGO_MANIFEST_DIR"), "/../code-hooks");
+    let workspace_lock = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Cargo.lock");
+    for (event, input_type) in [
+        ("pretool", "PreToolUse"),
+        ("posttool", "PostToolUse"),
+        ("notification", "Notification"),
+        ("stop", "Stop"),
+        ("subagentstop", "SubagentStop"),
+        ("userpromptsubmit", "UserPromptSubmit"),
+    ] {
+        let name = format!("{event}-hook");
+        Command::cargo_bin("hooktest")
+            .unwrap()
+            .current_dir(dir.path())
+            .args(["new", &name, "--event", event, "--path", code_hooks])
+            .assert()
+            .success()
+            .stdout(contains(format!("Created {event} hook in {name}/")));
+
+        let project = dir.path().join(&name);
+        let manifest = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains(&format!("name = \"{name}\"")));
+        assert!(manifest.contains("code-hooks = { path = "));
+        let main = fs::read_to_string(project.join("src/main.rs")).unwrap();
+        assert!(main.contains(&format!("let input = {input_type}::read()?;")));
+
+        // The generated hook builds against this checkout, using the
+        // workspace's locked dependency versions and a shared target dir
+        fs::copy(workspace_lock, project.join("Cargo.lock")).unwrap();
+        Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+            .current_dir(&project)
+            .env(
+                "CARGO_TARGET_DIR",
+                concat!(env!("CARGO_TARGET_TMPDIR"), "/scaffold"),
+            )
+            .args(["check", "--quiet"])
+            .assert()
+            .success();
+    }
+
+    // Without --path, the hook depends on the git repository
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["new", "git-hook", "--event", "stop"])
+        .assert()
+        .success();
+    let manifest = fs::read_to_string(dir.path().join("git-hook/Cargo.toml")).unwrap();
+    assert!(manifest.contains(r#"code-hooks = { git = "https://github.com/tenxhq/code-hooks" }"#));
+
+    // Existing directories aren't overwritten
+    Command::cargo_bin("hooktest")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["new", "stop-hook", "--event", "stop"])
+        .assert()
+        .failure()
+        .stderr(contains("already exists"));
+}
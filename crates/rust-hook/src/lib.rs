@@ -1,3 +1,6 @@
+pub mod runner;
+pub mod scope;
 pub mod utils;
 
-pub use utils::{find_project_root, is_rust_file};
+pub use scope::{Scope, Target};
+pub use utils::{command_timeout, find_project_root, is_rust_file, output_with_timeout};
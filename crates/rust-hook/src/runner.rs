@@ -0,0 +1,245 @@
+use anyhow::{Result, anyhow};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use crate::scope::{Target, ToolCommand};
+use crate::utils::{command_timeout, output_with_timeout};
+
+/// What a finished command produced
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Runs external programs, so the checks can be tested without a toolchain
+pub trait CommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput>;
+}
+
+/// Runs commands as real processes in a working directory, killing them
+/// after [`command_timeout`]
+pub struct SystemRunner {
+    working_dir: PathBuf,
+}
+
+impl SystemRunner {
+    pub fn new(working_dir: &Path) -> Self {
+        Self {
+            working_dir: working_dir.to_path_buf(),
+        }
+    }
+}
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let mut cmd = Command::new(program);
+        cmd.args(args).current_dir(&self.working_dir);
+
+        let shell_cmd = std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        log_command(&cmd, &self.working_dir.to_string_lossy(), &shell_cmd);
+
+        let timeout = command_timeout();
+        let output = output_with_timeout(&mut cmd, timeout)?
+            .ok_or_else(|| anyhow!("timed out after {}s", timeout.as_secs_f64()))?;
+
+        log_command_result(&output, &display_name(program, args));
+
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+}
+
+/// Format and lint the target, returning feedback for every problem found
+pub fn run_rust_tools(target: &Target, runner: &dyn CommandRunner) -> Result<Vec<String>> {
+    let mut feedback_messages = Vec::new();
+
+    // Run cargo fmt --all, or rustfmt on the edited file
+    eprintln!("[rust-hook] Running cargo fmt...");
+    match run_cargo_fmt(target, runner) {
+        Ok(output) => {
+            if !output.success {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                eprintln!("[rust-hook] cargo fmt failed with output:\n{stderr}");
+                feedback_messages.push(format!("cargo fmt failed:\n{stderr}"));
+            } else {
+                eprintln!("[rust-hook] cargo fmt succeeded");
+            }
+        }
+        Err(e) => {
+            eprintln!("[rust-hook] Error running cargo fmt: {e}");
+            feedback_messages.push(format!("Failed to run cargo fmt: {e}"));
+        }
+    }
+
+    // Run cargo clippy
+    eprintln!("[rust-hook] Running cargo clippy...");
+    match run_cargo_clippy(target, runner) {
+        Ok(output) => {
+            if !output.success {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                eprintln!("[rust-hook] cargo clippy found issues:\n{stderr}");
+                feedback_messages.push(format!("cargo clippy found warnings:\n{stderr}"));
+            } else {
+                eprintln!("[rust-hook] cargo clippy succeeded");
+            }
+        }
+        Err(e) => {
+            eprintln!("[rust-hook] Error running cargo clippy: {e}");
+            feedback_messages.push(format!("Failed to run cargo clippy: {e}"));
+        }
+    }
+
+    eprintln!(
+        "[rust-hook] Total feedback messages: {}",
+        feedback_messages.len()
+    );
+    Ok(feedback_messages)
+}
+
+fn run_cargo_fmt(target: &Target, runner: &dyn CommandRunner) -> Result<CommandOutput> {
+    run_tool(runner, &target.fmt_command())
+}
+
+fn run_cargo_clippy(target: &Target, runner: &dyn CommandRunner) -> Result<CommandOutput> {
+    let output = run_tool(runner, &target.clippy_command())?;
+
+    // Check if there are any warnings in stderr, even if clippy exited successfully
+    let stderr_str = String::from_utf8_lossy(&output.stderr);
+    let has_warnings = stderr_str.contains("warning:") || stderr_str.contains("error:");
+
+    Ok(CommandOutput {
+        success: !has_warnings,
+        ..output
+    })
+}
+
+fn run_tool(runner: &dyn CommandRunner, tool: &ToolCommand) -> Result<CommandOutput> {
+    let args: Vec<&str> = tool.args.iter().map(String::as_str).collect();
+    runner.run(tool.program, &args)
+}
+
+// Name a command for logging, e.g. "cargo clippy"
+fn display_name(program: &str, args: &[&str]) -> String {
+    match args.first() {
+        Some(subcommand) if program == "cargo" => format!("cargo {subcommand}"),
+        _ => program.to_string(),
+    }
+}
+
+// Helper function to log command details
+fn log_command(cmd: &Command, working_dir: &str, shell_cmd: &str) {
+    eprintln!("[rust-hook] Full command: {cmd:?}");
+    eprintln!("[rust-hook] Working directory: {working_dir}");
+    eprintln!("[rust-hook] Equivalent shell command: {shell_cmd}");
+}
+
+// Helper function to log command output
+fn log_command_result(output: &Output, cmd_name: &str) {
+    let exit_code = output.status.code().unwrap_or(-1);
+    eprintln!("[rust-hook] {cmd_name} exit code: {exit_code}");
+
+    if !output.stdout.is_empty() {
+        eprintln!(
+            "[rust-hook] {cmd_name} stdout:\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
+    if !output.stderr.is_empty() {
+        eprintln!(
+            "[rust-hook] {cmd_name} stderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Returns canned output for each program and subcommand, and records the
+    /// commands it was asked to run
+    #[derive(Default)]
+    struct MockRunner {
+        outputs: Vec<(&'static str, CommandOutput)>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl MockRunner {
+        fn with_output(mut self, name: &'static str, success: bool, stderr: &str) -> Self {
+            self.outputs.push((
+                name,
+                CommandOutput {
+                    success,
+                    stdout: Vec::new(),
+                    stderr: stderr.as_bytes().to_vec(),
+                },
+            ));
+            self
+        }
+    }
+
+    impl CommandRunner for MockRunner {
+        fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+            let name = display_name(program, args);
+            self.calls
+                .borrow_mut()
+                .push(format!("{program} {}", args.join(" ")));
+            self.outputs
+                .iter()
+                .find(|(expected, _)| *expected == name)
+                .map(|(_, output)| output.clone())
+                .ok_or_else(|| anyhow!("{name} not found"))
+        }
+    }
+
+    #[test]
+    fn test_clippy_warnings_become_feedback() {
+        let warning = "warning: unused variable: `x`\n --> src/lib.rs:2:9";
+        let runner = MockRunner::default()
+            .with_output("cargo fmt", true, "")
+            .with_output("cargo clippy", true, warning);
+
+        let feedback = run_rust_tools(&Target::Workspace, &runner).unwrap();
+        assert_eq!(feedback.len(), 1);
+        assert!(feedback[0].starts_with("cargo clippy found warnings:"));
+        assert!(feedback[0].contains(warning));
+        assert_eq!(
+            *runner.calls.borrow(),
+            [
+                "cargo fmt --all",
+                "cargo clippy --tests --examples --fix --allow-dirty"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clean_run_and_missing_tools() {
+        let runner = MockRunner::default()
+            .with_output("cargo fmt", true, "")
+            .with_output("cargo clippy", true, "    Finished dev profile");
+        assert!(
+            run_rust_tools(&Target::Workspace, &runner)
+                .unwrap()
+                .is_empty()
+        );
+
+        let feedback = run_rust_tools(&Target::Workspace, &MockRunner::default()).unwrap();
+        assert_eq!(
+            feedback,
+            [
+                "Failed to run cargo fmt: cargo fmt not found",
+                "Failed to run cargo clippy: cargo clippy not found"
+            ]
+        );
+    }
+}
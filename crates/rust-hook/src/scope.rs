@@ -0,0 +1,177 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::utils::{command_timeout, find_project_root, output_with_timeout};
+
+/// How much of the project to format and lint after an edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Scope {
+    /// Only the edited file, and clippy on the package that owns it
+    File,
+    /// The whole workspace
+    Workspace,
+}
+
+/// A workspace package, as reported by `cargo metadata`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    pub edition: String,
+    /// Directory containing the package's `Cargo.toml`
+    pub manifest_dir: PathBuf,
+}
+
+/// What the Rust tools run on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Every package in the workspace of the current directory
+    Workspace,
+    /// A single file and the package that owns it
+    File { path: String, package: Package },
+}
+
+/// A program and its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCommand {
+    pub program: &'static str,
+    pub args: Vec<String>,
+}
+
+impl fmt::Display for ToolCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Target {
+    /// Resolve the target for an edit to `file_path`. File scope falls back to
+    /// the workspace if the owning package can't be found.
+    pub fn for_edit(scope: Scope, file_path: &str) -> Target {
+        match scope {
+            Scope::Workspace => Target::Workspace,
+            // The tools run in the package directory, so the path must be
+            // absolute rather than relative to the current one
+            Scope::File => match find_owner(file_path) {
+                Ok(Some((path, package))) => Target::File {
+                    path: path.to_string_lossy().into_owned(),
+                    package,
+                },
+                Ok(None) => {
+                    eprintln!("[rust-hook] No package owns {file_path}, checking workspace");
+                    Target::Workspace
+                }
+                Err(e) => {
+                    eprintln!("[rust-hook] Failed to find package for {file_path}: {e}");
+                    Target::Workspace
+                }
+            },
+        }
+    }
+
+    /// Directory to run the commands in
+    pub fn working_dir(&self) -> &Path {
+        match self {
+            Target::Workspace => Path::new("."),
+            Target::File { package, .. } => &package.manifest_dir,
+        }
+    }
+
+    /// The formatting command: `cargo fmt --all`, or `rustfmt` on the file
+    pub fn fmt_command(&self) -> ToolCommand {
+        match self {
+            Target::Workspace => ToolCommand {
+                program: "cargo",
+                args: vec!["fmt".to_string(), "--all".to_string()],
+            },
+            Target::File { path, package } => ToolCommand {
+                program: "rustfmt",
+                args: vec![
+                    "--edition".to_string(),
+                    package.edition.clone(),
+                    path.clone(),
+                ],
+            },
+        }
+    }
+
+    /// The clippy command, limited to the owning package for a file
+    pub fn clippy_command(&self) -> ToolCommand {
+        let mut args = vec!["clippy".to_string()];
+        if let Target::File { package, .. } = self {
+            args.extend(["-p".to_string(), package.name.clone()]);
+        }
+        args.extend(
+            ["--tests", "--examples", "--fix", "--allow-dirty"]
+                .iter()
+                .map(|arg| arg.to_string()),
+        );
+        ToolCommand {
+            program: "cargo",
+            args,
+        }
+    }
+}
+
+/// Find the workspace package that owns `file_path` using `cargo metadata`.
+///
+/// Fails if cargo runs longer than [`command_timeout`], e.g. while waiting on
+/// a lock held by another build.
+pub fn find_package(file_path: &str) -> Result<Option<Package>> {
+    Ok(find_owner(file_path)?.map(|(_, package)| package))
+}
+
+/// The absolute path of `file_path` and the workspace package that owns it
+fn find_owner(file_path: &str) -> Result<Option<(PathBuf, Package)>> {
+    // cargo reports absolute manifest paths
+    let path = std::path::absolute(file_path)?;
+    let root = find_project_root(&path.to_string_lossy());
+    let timeout = command_timeout();
+    let mut cmd = Command::new("cargo");
+    cmd.args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(&root);
+    let output = output_with_timeout(&mut cmd, timeout)
+        .context("failed to run cargo metadata")?
+        .ok_or_else(|| {
+            anyhow::anyhow!("cargo metadata timed out after {}s", timeout.as_secs_f64())
+        })?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "cargo metadata failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout)?;
+    Ok(package_for_file(&metadata, &path).map(|package| (path, package)))
+}
+
+/// Pick the package whose directory most closely contains `file_path` from
+/// `cargo metadata` output
+pub fn package_for_file(metadata: &Value, file_path: &Path) -> Option<Package> {
+    metadata
+        .get("packages")?
+        .as_array()?
+        .iter()
+        .filter_map(|package| {
+            let manifest_dir = Path::new(package.get("manifest_path")?.as_str()?).parent()?;
+            if !file_path.starts_with(manifest_dir) {
+                return None;
+            }
+            Some(Package {
+                name: package.get("name")?.as_str()?.to_string(),
+                edition: package
+                    .get("edition")
+                    .and_then(Value::as_str)
+                    .unwrap_or("2021")
+                    .to_string(),
+                manifest_dir: manifest_dir.to_path_buf(),
+            })
+        })
+        .max_by_key(|package| package.manifest_dir.components().count())
+}
@@ -1,4 +1,9 @@
+use code_hooks::path_matches;
+use std::io::{self, Read};
 use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 /// Find the nearest ancestor directory containing a `Cargo.toml` file.
 /// Returns the directory path as a `String`.
@@ -20,5 +25,76 @@ pub fn find_project_root(file_path: &str) -> String {
 
 /// Determine whether the provided path refers to a Rust source file.
 pub fn is_rust_file(file_path: &str) -> bool {
-    file_path.ends_with(".rs")
+    path_matches(file_path, &["**/*.rs"])
+}
+
+/// Default limit on how long a cargo command may run
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often to check whether a command has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The cargo command timeout: `RUST_HOOK_TIMEOUT` seconds if set and valid,
+/// otherwise [`DEFAULT_TIMEOUT`].
+pub fn command_timeout() -> Duration {
+    match std::env::var("RUST_HOOK_TIMEOUT") {
+        Ok(value) => match value
+            .parse::<f64>()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        {
+            Some(timeout) => timeout,
+            None => {
+                eprintln!("[rust-hook] Ignoring invalid RUST_HOOK_TIMEOUT '{value}'");
+                DEFAULT_TIMEOUT
+            }
+        },
+        Err(_) => DEFAULT_TIMEOUT,
+    }
+}
+
+/// Run a command to completion and capture its output, like
+/// [`Command::output`], but kill it if it runs longer than `timeout`.
+///
+/// Returns `None` if the command was killed.
+pub fn output_with_timeout(cmd: &mut Command, timeout: Duration) -> io::Result<Option<Output>> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // The readers are left to finish on their own, since processes
+            // the command spawned may still hold its pipes open
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
+/// Read a pipe to the end on a separate thread
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
 }
@@ -1,5 +1,10 @@
-use rust_hook::utils::{find_project_root, is_rust_file};
+use rust_hook::scope::{Scope, Target, find_package};
+use rust_hook::utils::{find_project_root, is_rust_file, output_with_timeout};
 use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 use tempfile::TempDir;
 
 #[test]
@@ -22,6 +27,56 @@ fn test_find_project_root() {
     assert_eq!(project_root, temp_dir.path().to_str().unwrap());
 }
 
+#[test]
+fn test_file_scope_targets_owning_package() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[workspace]\nmembers = [\"alpha\", \"beta\"]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+    for name in ["alpha", "beta"] {
+        let src = temp_dir.path().join(name).join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(
+            temp_dir.path().join(name).join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+        )
+        .unwrap();
+        fs::write(src.join("lib.rs"), "").unwrap();
+    }
+
+    let file_path = temp_dir.path().join("beta/src/lib.rs");
+    let file_path = file_path.to_str().unwrap();
+    let package = find_package(file_path).unwrap().unwrap();
+    assert_eq!(package.name, "beta");
+    assert_eq!(package.edition, "2021");
+
+    let target = Target::File {
+        path: file_path.to_string(),
+        package,
+    };
+    assert_eq!(target.working_dir(), temp_dir.path().join("beta"));
+
+    let fmt = target.fmt_command();
+    assert_eq!(fmt.program, "rustfmt");
+    assert_eq!(fmt.args, ["--edition", "2021", file_path]);
+
+    let clippy = target.clippy_command();
+    assert_eq!(clippy.program, "cargo");
+    assert_eq!(clippy.args[..3], ["clippy", "-p", "beta"]);
+    assert!(!clippy.args.iter().any(|arg| arg == "alpha"));
+
+    // Workspace scope keeps checking everything
+    assert_eq!(Target::Workspace.fmt_command().args, ["fmt", "--all"]);
+    assert!(
+        !Target::Workspace
+            .clippy_command()
+            .args
+            .contains(&"-p".to_string())
+    );
+}
+
 #[test]
 fn test_rust_file_check() {
     assert!(is_rust_file("test.rs"));
@@ -71,3 +126,118 @@ fn test_subcommand_help() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("stop"));
 }
+
+/// Write a fake `cargo` into `dir` that never finishes
+fn write_sleeping_cargo(dir: &Path) {
+    let shim = dir.join("cargo");
+    fs::write(&shim, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    fs::set_permissions(&shim, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn test_output_with_timeout() {
+    let output = output_with_timeout(Command::new("echo").arg("done"), Duration::from_secs(10))
+        .unwrap()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"done\n");
+
+    let shim_dir = TempDir::new().unwrap();
+    write_sleeping_cargo(shim_dir.path());
+    let start = Instant::now();
+    let output = output_with_timeout(
+        &mut Command::new(shim_dir.path().join("cargo")),
+        Duration::from_millis(200),
+    )
+    .unwrap();
+    assert!(output.is_none());
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_posttool_cargo_timeout() {
+    let shim_dir = TempDir::new().unwrap();
+    write_sleeping_cargo(shim_dir.path());
+    let path = format!(
+        "{}:{}",
+        shim_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let input = serde_json::json!({
+        "session_id": "test-session",
+        "transcript_path": "/path/to/transcript",
+        "tool_name": "Edit",
+        "tool_input": {},
+        "tool_response": {"filePath": "/tmp/project/src/lib.rs"}
+    });
+    let input_file = tempfile::NamedTempFile::new().unwrap();
+    fs::write(input_file.path(), input.to_string()).unwrap();
+
+    let start = Instant::now();
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-hook"))
+        .arg("posttool")
+        .env("PATH", path)
+        .env("RUST_HOOK_TIMEOUT", "0.5")
+        .current_dir(shim_dir.path())
+        .stdin(fs::File::open(input_file.path()).unwrap())
+        .stderr(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["decision"], "block");
+    let reason = response["reason"].as_str().unwrap();
+    assert!(reason.contains("Failed to run cargo fmt: timed out after 0.5s"));
+    assert!(reason.contains("Failed to run cargo clippy: timed out after 0.5s"));
+}
+
+#[test]
+fn test_posttool_file_scope_metadata_timeout() {
+    let shim_dir = TempDir::new().unwrap();
+    write_sleeping_cargo(shim_dir.path());
+    fs::write(shim_dir.path().join("Cargo.toml"), "").unwrap();
+    let path = format!(
+        "{}:{}",
+        shim_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let file_path = shim_dir.path().join("src/lib.rs");
+    let input = serde_json::json!({
+        "session_id": "test-session",
+        "transcript_path": "/path/to/transcript",
+        "tool_name": "Edit",
+        "tool_input": {},
+        "tool_response": {"filePath": file_path}
+    });
+    let input_file = tempfile::NamedTempFile::new().unwrap();
+    fs::write(input_file.path(), input.to_string()).unwrap();
+
+    let start = Instant::now();
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-hook"))
+        .args(["posttool", "--scope", "file"])
+        .env("PATH", path)
+        .env("RUST_HOOK_TIMEOUT", "0.5")
+        .current_dir(shim_dir.path())
+        .stdin(fs::File::open(input_file.path()).unwrap())
+        .output()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    // A hung cargo metadata falls back to checking the workspace
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cargo metadata timed out after 0.5s"));
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reason = response["reason"].as_str().unwrap();
+    assert!(reason.contains("Failed to run cargo fmt: timed out after 0.5s"));
+}
+
+#[test]
+fn test_file_scope_relative_path_is_absolute() {
+    // Integration tests run in the package directory
+    let Target::File { path, package } = Target::for_edit(Scope::File, "src/scope.rs") else {
+        panic!("expected a file target");
+    };
+    assert_eq!(package.name, "rust-hook");
+    assert_eq!(path, concat!(env!("CARGO_MANIFEST_DIR"), "/src/scope.rs"));
+}